@@ -0,0 +1,22 @@
+/// Integrity snapshot of a chain from [`Chain::audit`](crate::Chain::audit), listing every
+/// problem found rather than stopping at the first one.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct AuditReport {
+    /// Whether the first block is the configured genesis.
+    pub genesis_matches: bool,
+    /// Heights of blocks whose `prev_hash` isn't the hash of the block below.
+    pub broken_links: Vec<u64>,
+    /// Heights of blocks whose content doesn't hash to their stored hash.
+    pub invalid_hashes: Vec<u64>,
+    /// Heights of blocks timestamped before the block below or too far in the future.
+    pub timestamp_anomalies: Vec<u64>,
+}
+
+impl AuditReport {
+    pub fn is_clean(&self) -> bool {
+        self.genesis_matches
+            && self.broken_links.is_empty()
+            && self.invalid_hashes.is_empty()
+            && self.timestamp_anomalies.is_empty()
+    }
+}
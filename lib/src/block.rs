@@ -1,6 +1,8 @@
 use std::{
+    collections::BTreeMap,
     fmt::Display,
-    time::{SystemTime, UNIX_EPOCH},
+    sync::OnceLock,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 #[cfg(feature = "serde")]
@@ -8,15 +10,111 @@ use serde::{Deserialize, Serialize};
 
 pub type Payload = String;
 
-use crate::hash::{Hash, Hashable};
+/// Key-value pairs attached to a block next to its payload. Being a `BTreeMap`, it is always
+/// hashed in key order, whatever order the entries were inserted in.
+pub type Metadata = BTreeMap<String, String>;
+
+/// Largest payload, in bytes, a block accepts.
+pub const MAX_PAYLOAD_BYTES: usize = 1024 * 1024;
+
+/// Payload left in place of a redacted one, see [`Block::is_redacted`].
+pub const REDACTED_PAYLOAD: &str = "REDACTED";
+
+/// Bytes a block takes on top of what it hashes: its own stored hash.
+pub const BLOCK_HEADER_OVERHEAD: usize = 32;
+
+/// Consensus rule version of genesis and of blocks mined before versioning.
+pub const GENESIS_RULE_VERSION: u16 = 1;
+
+/// First consensus rule version hashing the payload length ahead of the payload.
+pub const PAYLOAD_LENGTH_RULE_VERSION: u16 = 3;
+
+/// Newest consensus rule version this node knows how to validate.
+pub const LATEST_RULE_VERSION: u16 = PAYLOAD_LENGTH_RULE_VERSION;
+
+use crate::{
+    chain::ValidationError,
+    hash::{Hash, Hashable},
+};
 
 #[cfg(feature = "serde")]
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[serde(try_from = "SerializedBlock")]
 pub struct Block {
     timestamp: SystemTime,
     prev_hash: Hash,
     hash: Hash,
+    payload_hash: Hash,
     payload: Payload,
+    #[serde(default)]
+    ttl: Option<Duration>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    not_before: Option<SystemTime>,
+    #[serde(default = "genesis_rule_version")]
+    version: u16,
+    #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+    metadata: Metadata,
+    // a redacted block has no hash left to check: the flag round-trips so a chain can be saved
+    // and read back, but every method taking blocks from peers refuses redacted ones
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    redacted: bool,
+    #[serde(skip)]
+    hash_cache: HashCache,
+}
+
+/// Wire form of a [`Block`], only turned into one once its stored hash is checked against its
+/// [`Block::canonical_bytes`]. Unknown fields are refused rather than dropped, they would most
+/// likely come from a newer format this node can't hash.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SerializedBlock {
+    timestamp: SystemTime,
+    prev_hash: Hash,
+    hash: Hash,
+    payload_hash: Hash,
+    payload: Payload,
+    #[serde(default)]
+    ttl: Option<Duration>,
+    #[serde(default)]
+    not_before: Option<SystemTime>,
+    #[serde(default = "genesis_rule_version")]
+    version: u16,
+    #[serde(default)]
+    metadata: Metadata,
+    #[serde(default)]
+    redacted: bool,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SerializedBlock> for Block {
+    type Error = String;
+
+    fn try_from(serialized: SerializedBlock) -> Result<Self, Self::Error> {
+        let block = Self {
+            timestamp: serialized.timestamp,
+            prev_hash: serialized.prev_hash,
+            hash: serialized.hash,
+            payload_hash: serialized.payload_hash,
+            payload: serialized.payload,
+            ttl: serialized.ttl,
+            not_before: serialized.not_before,
+            version: serialized.version,
+            metadata: serialized.metadata,
+            redacted: serialized.redacted,
+            hash_cache: HashCache::default(),
+        };
+        if block.timestamp < UNIX_EPOCH {
+            return Err("block timestamp is before the Unix epoch".to_string());
+        }
+        if !block.has_valid_hash() {
+            return Err(format!(
+                "block hash {} does not match its content",
+                block.hash
+            ));
+        }
+        Ok(block)
+    }
 }
 
 #[cfg(not(feature = "serde"))]
@@ -25,27 +123,177 @@ pub struct Block {
     timestamp: SystemTime,
     prev_hash: Hash,
     hash: Hash,
+    payload_hash: Hash,
     payload: Payload,
+    ttl: Option<Duration>,
+    not_before: Option<SystemTime>,
+    version: u16,
+    metadata: Metadata,
+    redacted: bool,
+    hash_cache: HashCache,
+}
+
+/// Hashes of a block's own content, computed on first use and reset whenever a hashed field
+/// changes: [`Hashable::make_hash`] and the hash of the current payload that
+/// [`Block::has_valid_hash`] checks against the committed payload hash. [`Hashable::bytes`] and
+/// [`Block::matches_payload`], given any payload, are never memoized. The cache isn't part of
+/// the block: it compares equal to any other cache and is never serialized or hashed.
+#[derive(Debug, Default, Clone)]
+struct HashCache {
+    block: OnceLock<Hash>,
+    payload: OnceLock<Hash>,
+}
+
+impl HashCache {
+    fn block_hash<F: FnOnce() -> Hash>(&self, compute: F) -> Hash {
+        *self.block.get_or_init(compute)
+    }
+
+    fn payload_hash<F: FnOnce() -> Hash>(&self, compute: F) -> Hash {
+        *self.payload.get_or_init(compute)
+    }
+
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl PartialEq for HashCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for HashCache {}
+
+#[cfg(feature = "serde")]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub struct BlockHeader {
+    timestamp: SystemTime,
+    prev_hash: Hash,
+    hash: Hash,
+    payload_hash: Hash,
+    ttl: Option<Duration>,
+    not_before: Option<SystemTime>,
+    version: u16,
+}
+
+#[cfg(not(feature = "serde"))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BlockHeader {
+    timestamp: SystemTime,
+    prev_hash: Hash,
+    hash: Hash,
+    payload_hash: Hash,
+    ttl: Option<Duration>,
+    not_before: Option<SystemTime>,
+    version: u16,
+}
+
+impl BlockHeader {
+    pub fn get_timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    pub fn get_prev_hash(&self) -> Hash {
+        self.prev_hash
+    }
+
+    pub fn get_hash(&self) -> Hash {
+        self.hash
+    }
+
+    pub fn get_payload_hash(&self) -> Hash {
+        self.payload_hash
+    }
+
+    pub fn get_ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    pub fn get_not_before(&self) -> Option<SystemTime> {
+        self.not_before
+    }
+
+    pub fn get_version(&self) -> u16 {
+        self.version
+    }
 }
 
 impl Block {
+    /// Infallible version of [`Block::try_new`], panicking on invalid input.
     pub fn new(timestamp: SystemTime, prev_hash: Hash, payload: Payload) -> Self {
+        Self::try_new(timestamp, prev_hash, payload).expect("valid block")
+    }
+
+    /// Builds and hashes a block, refusing a timestamp before the Unix epoch (which can't be
+    /// hashed) and payloads over [`MAX_PAYLOAD_BYTES`].
+    pub fn try_new(
+        timestamp: SystemTime,
+        prev_hash: Hash,
+        payload: Payload,
+    ) -> Result<Self, BlockError> {
+        if timestamp < UNIX_EPOCH {
+            return Err(BlockError::PreEpochTimestamp);
+        }
+        if payload.len() > MAX_PAYLOAD_BYTES {
+            return Err(BlockError::PayloadTooLarge {
+                size: payload.len(),
+            });
+        }
+
         let mut block = Self {
             timestamp,
             prev_hash,
-            hash: Hash::default(),
+            hash: Hash::zero(),
+            payload_hash: Hash::from_bytes(payload.as_bytes()),
             payload,
+            ttl: None,
+            not_before: None,
+            version: GENESIS_RULE_VERSION,
+            metadata: Metadata::new(),
+            redacted: false,
+            hash_cache: HashCache::default(),
         };
 
         block.hash = block.make_hash();
-        block
+        Ok(block)
     }
 
+    /// Reassembles a block from a previously downloaded header and its payload, making sure the
+    /// payload is the one the header committed to.
+    pub fn from_header_and_payload(
+        header: BlockHeader,
+        payload: Payload,
+    ) -> Result<Self, ValidationError> {
+        let mut block = Self::try_new(header.timestamp, header.prev_hash, payload)
+            .map_err(ValidationError::InvalidBlock)?;
+        if let Some(ttl) = header.ttl {
+            block = block.with_ttl(ttl);
+        }
+        if let Some(not_before) = header.not_before {
+            block = block.with_not_before(not_before);
+        }
+        block = block.with_version(header.version);
+        if block.hash != header.hash {
+            return Err(ValidationError::InvalidHash);
+        }
+        Ok(block)
+    }
+
+    /// Genesis block of the default network. It has no predecessor, so its `prev_hash` is
+    /// [`Hash::zero`].
     pub fn genesis() -> Self {
+        Self::new(UNIX_EPOCH, Hash::zero(), "Genesis block".to_string())
+    }
+
+    /// Genesis block of a private network, derived from its seed so that every node started with
+    /// the same seed agrees on it.
+    pub fn network_genesis(seed: &str) -> Self {
         Self::new(
-            SystemTime::from(UNIX_EPOCH),
-            Hash::from_bytes(&[1]),
-            "Genesis block".to_string(),
+            UNIX_EPOCH,
+            Hash::from_bytes(seed.as_bytes()),
+            seed.to_string(),
         )
     }
 
@@ -54,6 +302,44 @@ impl Block {
         Self::new(now, prev_block.hash, payload)
     }
 
+    /// Marks the payload as only meaningful for `ttl` after the block's timestamp. The block
+    /// stays in the chain once expired, the TTL being hashed in like any other field.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self.hash_cache.reset();
+        self.hash = self.make_hash();
+        self
+    }
+
+    /// Time-locks the block: chains holding it don't validate before `not_before`, see
+    /// [`Chain::validate_at`](crate::Chain::validate_at). Hashed in like the TTL.
+    pub fn with_not_before(mut self, not_before: SystemTime) -> Self {
+        self.not_before = Some(not_before);
+        self.hash_cache.reset();
+        self.hash = self.make_hash();
+        self
+    }
+
+    /// Tags the block with the consensus rule version it has to be validated under. Like the
+    /// TTL, the version is hashed in.
+    pub fn with_version(mut self, version: u16) -> Self {
+        if version != self.version {
+            self.version = version;
+            self.hash_cache.reset();
+            self.hash = self.make_hash();
+        }
+        self
+    }
+
+    /// Attaches `metadata` to the block, hashed in after the payload. Headers don't carry it, so
+    /// [`Block::from_header_and_payload`] only rebuilds blocks without metadata.
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self.hash_cache.reset();
+        self.hash = self.make_hash();
+        self
+    }
+
     pub fn get_prev_hash(&self) -> Hash {
         self.prev_hash
     }
@@ -62,6 +348,89 @@ impl Block {
         self.timestamp
     }
 
+    pub fn get_ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    pub fn get_not_before(&self) -> Option<SystemTime> {
+        self.not_before
+    }
+
+    pub fn get_version(&self) -> u16 {
+        self.version
+    }
+
+    pub fn get_metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Whether the payload was replaced by [`REDACTED_PAYLOAD`]. A redacted block keeps the hash
+    /// of its original content, which can't be recomputed anymore and has to be trusted.
+    pub fn is_redacted(&self) -> bool {
+        self.redacted
+    }
+
+    pub(crate) fn redact(&mut self) {
+        self.payload = REDACTED_PAYLOAD.to_string();
+        self.redacted = true;
+        self.hash_cache.reset();
+    }
+
+    /// Hash of the payload alone, committed to by the block hash. Unlike the block hash it
+    /// survives redaction as-is, so a payload fetched again later can be checked against it.
+    pub fn get_payload_hash(&self) -> Hash {
+        self.payload_hash
+    }
+
+    /// Whether `payload` is the body this block committed to, e.g. one re-fetched from a peer
+    /// after redaction. Only the payload is hashed, not the whole block.
+    pub fn matches_payload(&self, payload: &str) -> bool {
+        Hash::from_bytes(payload.as_bytes()) == self.payload_hash
+    }
+
+    /// Whether the stored hash matches the block's content. Redacted blocks only need to carry
+    /// the redaction marker, so a redacted block can't smuggle in a different payload. The
+    /// payload and the block are each hashed once, later calls reuse both until a hashed field
+    /// changes.
+    pub fn has_valid_hash(&self) -> bool {
+        if self.redacted {
+            self.payload == REDACTED_PAYLOAD
+        } else {
+            let payload_hash = self
+                .hash_cache
+                .payload_hash(|| Hash::from_bytes(self.payload.as_bytes()));
+            payload_hash == self.payload_hash && self.hash == self.make_hash()
+        }
+    }
+
+    /// Largest hash with at least `bits` leading zero bits, which a block hash must not exceed
+    /// to meet difficulty `bits`. Difficulties past 256 bits only leave [`Hash::zero`].
+    pub fn difficulty_target(bits: u32) -> Hash {
+        let mut target = [0xff; 32];
+        for (i, byte) in target.iter_mut().enumerate() {
+            let zeros = bits.saturating_sub(8 * i as u32).min(8);
+            *byte = 0xffu8.checked_shr(zeros).unwrap_or(0);
+        }
+        Hash::from_digest(target)
+    }
+
+    /// Whether the block hash has at least `bits` leading zero bits.
+    pub fn meets_target(&self, bits: u32) -> bool {
+        self.hash <= Self::difficulty_target(bits)
+    }
+
+    /// Whether the block is still time-locked at `now`. Blocks without a release time never are.
+    pub fn is_time_locked(&self, now: SystemTime) -> bool {
+        self.not_before.is_some_and(|not_before| now < not_before)
+    }
+
+    /// Whether the payload's TTL has elapsed at `now`. Blocks without a TTL never expire.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.ttl
+            .map(|ttl| self.timestamp + ttl <= now)
+            .unwrap_or(false)
+    }
+
     pub fn get_payload(&self) -> &Payload {
         &self.payload
     }
@@ -70,14 +439,128 @@ impl Block {
         self.hash
     }
 
+    /// Approximate size of the block: the bytes it hashes plus [`BLOCK_HEADER_OVERHEAD`].
+    pub fn size_bytes(&self) -> usize {
+        self.bytes().len() + BLOCK_HEADER_OVERHEAD
+    }
+
+    pub fn header(&self) -> BlockHeader {
+        BlockHeader {
+            timestamp: self.timestamp,
+            prev_hash: self.prev_hash,
+            hash: self.hash,
+            payload_hash: self.payload_hash,
+            ttl: self.ttl,
+            not_before: self.not_before,
+            version: self.version,
+        }
+    }
+
+    /// Binary encoding used by the write-ahead log. Unlike [`Hashable::bytes`] it keeps the full
+    /// timestamp precision and the stored hash, so the block decodes back exactly.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend(encode_duration(
+            self.timestamp.duration_since(UNIX_EPOCH).unwrap(),
+        ));
+        bytes.extend(self.prev_hash.bytes());
+        bytes.extend(self.hash.bytes());
+        bytes.extend(self.payload_hash.bytes());
+        // flags: bit 0 for a TTL following, bit 1 for a redacted payload, bit 2 for a rule
+        // version following, bit 3 for metadata following, bit 4 for a release time following
+        let versioned = self.version != GENESIS_RULE_VERSION;
+        bytes.push(
+            u8::from(self.ttl.is_some())
+                | u8::from(self.redacted) << 1
+                | u8::from(versioned) << 2
+                | u8::from(!self.metadata.is_empty()) << 3
+                | u8::from(self.not_before.is_some()) << 4,
+        );
+        if let Some(ttl) = self.ttl {
+            bytes.extend(encode_duration(ttl));
+        }
+        if let Some(not_before) = self.not_before {
+            bytes.extend(encode_duration(
+                not_before.duration_since(UNIX_EPOCH).unwrap_or_default(),
+            ));
+        }
+        if versioned {
+            bytes.extend(self.version.to_le_bytes());
+        }
+        if !self.metadata.is_empty() {
+            bytes.extend(encode_metadata(&self.metadata));
+        }
+        bytes.extend(self.payload.as_bytes());
+
+        bytes
+    }
+
+    pub(crate) fn decode(mut bytes: &[u8]) -> Option<Self> {
+        let timestamp = UNIX_EPOCH + decode_duration(take(&mut bytes, 16)?)?;
+        let prev_hash = Hash::from_digest(take(&mut bytes, 32)?.try_into().ok()?);
+        let hash = Hash::from_digest(take(&mut bytes, 32)?.try_into().ok()?);
+        let payload_hash = Hash::from_digest(take(&mut bytes, 32)?.try_into().ok()?);
+        let flags = take(&mut bytes, 1)?[0];
+        if flags & !0b1_1111 != 0 {
+            return None;
+        }
+        let ttl = match flags & 1 {
+            0 => None,
+            _ => Some(decode_duration(take(&mut bytes, 16)?)?),
+        };
+        let not_before = match flags & 0b1_0000 {
+            0 => None,
+            _ => Some(UNIX_EPOCH + decode_duration(take(&mut bytes, 16)?)?),
+        };
+        let version = match flags & 0b100 {
+            0 => GENESIS_RULE_VERSION,
+            _ => u16::from_le_bytes(take(&mut bytes, 2)?.try_into().ok()?),
+        };
+        let mut metadata = Metadata::new();
+        if flags & 0b1000 != 0 {
+            let len = u32::from_le_bytes(take(&mut bytes, 4)?.try_into().ok()?);
+            for _ in 0..len {
+                let key = decode_string(&mut bytes)?;
+                metadata.insert(key, decode_string(&mut bytes)?);
+            }
+        }
+
+        Some(Self {
+            timestamp,
+            prev_hash,
+            hash,
+            payload_hash,
+            payload: String::from_utf8(bytes.to_vec()).ok()?,
+            ttl,
+            not_before,
+            version,
+            metadata,
+            redacted: flags & 0b10 != 0,
+            hash_cache: HashCache::default(),
+        })
+    }
+
     #[allow(dead_code)]
     pub(crate) fn set_payload(&mut self, payload: Payload) {
         self.payload = payload;
+        self.hash_cache.reset();
     }
 }
 
-impl Hashable for Block {
-    fn bytes(&self) -> Vec<u8> {
+impl Block {
+    /// The exact bytes a block's hash is computed over: millisecond timestamp, previous hash,
+    /// payload hash, payload, then the optional fields, each behind its own tag byte so no two
+    /// blocks share an encoding: the TTL in milliseconds (`L`), the release time in milliseconds
+    /// since the epoch (`T`), the rule version if past [`GENESIS_RULE_VERSION`] (`V`) and the
+    /// metadata entry count and entries in key order (`M`). Everything that (de)serializes a
+    /// block must preserve these, or the stored hash stops matching.
+    ///
+    /// From [`PAYLOAD_LENGTH_RULE_VERSION`] on, everything after the payload hash has a fixed
+    /// layout instead: the payload length as a little-endian `u64`, the payload, the version, a
+    /// flags byte (bit 0 for a TTL following, bit 1 for a release time following), those fields
+    /// and the metadata, entry count included even when 0. Where each field ends no longer
+    /// depends on the payload hash to tell.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
         let mut bytes = vec![];
         bytes.extend(
             self.timestamp
@@ -88,26 +571,134 @@ impl Hashable for Block {
         );
 
         bytes.extend(self.prev_hash.bytes());
+        bytes.extend(self.payload_hash.bytes());
+        if self.version >= PAYLOAD_LENGTH_RULE_VERSION {
+            bytes.extend((self.payload.len() as u64).to_le_bytes());
+            bytes.extend(self.payload.as_bytes());
+            bytes.extend(self.version.to_le_bytes());
+            bytes.push(u8::from(self.ttl.is_some()) | u8::from(self.not_before.is_some()) << 1);
+            if let Some(ttl) = self.ttl {
+                bytes.extend(ttl.as_millis().to_le_bytes());
+            }
+            if let Some(not_before) = self.not_before {
+                bytes.extend(millis_since_epoch(not_before).to_le_bytes());
+            }
+            bytes.extend(encode_metadata(&self.metadata));
+            return bytes;
+        }
+
         bytes.extend(self.payload.as_bytes());
+        if let Some(ttl) = self.ttl {
+            bytes.push(b'L');
+            bytes.extend(ttl.as_millis().to_le_bytes());
+        }
+        if let Some(not_before) = self.not_before {
+            bytes.push(b'T');
+            bytes.extend(millis_since_epoch(not_before).to_le_bytes());
+        }
+        if self.version != GENESIS_RULE_VERSION {
+            bytes.push(b'V');
+            bytes.extend(self.version.to_le_bytes());
+        }
+        if !self.metadata.is_empty() {
+            bytes.push(b'M');
+            bytes.extend(encode_metadata(&self.metadata));
+        }
 
         bytes
     }
 }
 
+impl Hashable for Block {
+    fn bytes(&self) -> Vec<u8> {
+        self.canonical_bytes()
+    }
+
+    /// Hashes the block's content once, later calls reuse the result until a hashed field
+    /// changes. Validation checks every block against it, possibly several times.
+    fn make_hash(&self) -> Hash {
+        self.hash_cache
+            .block_hash(|| Hash::from_bytes(&self.bytes()))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BlockError {
+    PreEpochTimestamp,
+    PayloadTooLarge { size: usize },
+}
+
+#[cfg(feature = "serde")]
+fn genesis_rule_version() -> u16 {
+    GENESIS_RULE_VERSION
+}
+
+fn millis_since_epoch(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+/// The entry count, then each entry as its length-prefixed key and value, so no two maps
+/// encode the same.
+fn encode_metadata(metadata: &Metadata) -> Vec<u8> {
+    let mut bytes = (metadata.len() as u32).to_le_bytes().to_vec();
+    for (key, value) in metadata {
+        for field in [key, value] {
+            bytes.extend((field.len() as u32).to_le_bytes());
+            bytes.extend(field.as_bytes());
+        }
+    }
+    bytes
+}
+
+fn decode_string(bytes: &mut &[u8]) -> Option<String> {
+    let len = u32::from_le_bytes(take(bytes, 4)?.try_into().ok()?);
+    String::from_utf8(take(bytes, usize::try_from(len).ok()?)?.to_vec()).ok()
+}
+
+fn encode_duration(duration: Duration) -> [u8; 16] {
+    duration.as_nanos().to_le_bytes()
+}
+
+fn decode_duration(bytes: &[u8]) -> Option<Duration> {
+    let nanos = u128::from_le_bytes(bytes.try_into().ok()?);
+    Some(Duration::new(
+        u64::try_from(nanos / 1_000_000_000).ok()?,
+        (nanos % 1_000_000_000) as u32,
+    ))
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if bytes.len() < len {
+        return None;
+    }
+    let (head, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Some(head)
+}
+
 impl Default for Block {
     fn default() -> Self {
         Self {
-            timestamp: SystemTime::from(UNIX_EPOCH),
+            timestamp: UNIX_EPOCH,
             prev_hash: Hash::default(),
             hash: Hash::default(),
+            payload_hash: Hash::default(),
             payload: String::default(),
+            ttl: None,
+            not_before: None,
+            version: GENESIS_RULE_VERSION,
+            metadata: Metadata::new(),
+            redacted: false,
+            hash_cache: HashCache::default(),
         }
     }
 }
 
 impl Display for Block {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Block -")?;
+        writeln!(f, "Block {} -", self.hash.short())?;
         writeln!(
             f,
             "Timestamp     : {}",
@@ -125,9 +716,12 @@ impl Display for Block {
 
 #[cfg(test)]
 mod test {
-    use super::Block;
-    use crate::hash::Hash;
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use super::{Block, BlockError, Metadata, MAX_PAYLOAD_BYTES};
+    use crate::{
+        chain::ValidationError,
+        hash::{Hash, Hashable},
+    };
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
     #[test]
     fn test_field() {
@@ -159,10 +753,363 @@ mod test {
                 .to_le_bytes(),
         );
         block_bytes.extend(prev_hash.bytes());
+        block_bytes.extend(Hash::from_bytes(payload_str.as_bytes()).bytes());
         block_bytes.extend(payload_str.bytes());
 
         let expected_hash = Hash::from_bytes(&block_bytes);
 
         assert_eq!(expected_hash, block.get_hash());
     }
+
+    #[test]
+    fn test_try_new() {
+        let timestamp = SystemTime::now();
+        let prev_hash = Hash::from_bytes("123".as_bytes());
+        let payload = String::from("Hello world!");
+
+        assert_eq!(
+            Ok(Block::new(timestamp, prev_hash, payload.clone())),
+            Block::try_new(timestamp, prev_hash, payload)
+        );
+    }
+
+    #[test]
+    fn test_try_new_pre_epoch() {
+        let timestamp = UNIX_EPOCH - Duration::from_secs(1);
+
+        assert_eq!(
+            Err(BlockError::PreEpochTimestamp),
+            Block::try_new(timestamp, Hash::default(), "Hello world!".to_string())
+        );
+    }
+
+    #[test]
+    fn test_try_new_payload_too_large() {
+        let payload = "a".repeat(MAX_PAYLOAD_BYTES + 1);
+
+        assert_eq!(
+            Err(BlockError::PayloadTooLarge {
+                size: MAX_PAYLOAD_BYTES + 1
+            }),
+            Block::try_new(SystemTime::now(), Hash::default(), payload)
+        );
+    }
+
+    #[test]
+    fn test_size_bytes() {
+        let small = Block::mine(&Block::genesis(), "Hello".to_string());
+        let large = Block::mine(&Block::genesis(), "Hello world!".to_string());
+
+        assert_eq!(small.bytes().len() + 32, small.size_bytes());
+        assert_eq!(small.size_bytes() + 7, large.size_bytes());
+    }
+
+    #[test]
+    fn test_from_header_and_payload() {
+        let block = Block::mine(&Block::genesis(), "Hello world!".to_string());
+
+        assert_eq!(
+            Ok(block.clone()),
+            Block::from_header_and_payload(block.header(), "Hello world!".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_header_and_mismatched_payload() {
+        let block = Block::mine(&Block::genesis(), "Hello world!".to_string());
+
+        assert_eq!(
+            Err(ValidationError::InvalidHash),
+            Block::from_header_and_payload(block.header(), "Goodbye world!".to_string())
+        );
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let block = Block::mine(&Block::genesis(), "Hello world!".to_string());
+
+        assert_eq!(Some(block.clone()), Block::decode(&block.encode()));
+        assert_eq!(None, Block::decode(&block.encode()[..40]));
+
+        let block = block.with_ttl(Duration::from_secs(60));
+        assert_eq!(Some(block.clone()), Block::decode(&block.encode()));
+
+        let block = block.with_version(2);
+        assert_eq!(Some(block.clone()), Block::decode(&block.encode()));
+
+        let metadata = [("producer", "node-1"), ("tag", "")]
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        let block = block.with_metadata(metadata);
+        assert_eq!(Some(block.clone()), Block::decode(&block.encode()));
+    }
+
+    #[test]
+    fn test_optional_fields_are_tagged() {
+        let block = Block::mine(&Block::genesis(), "Hello world!".to_string());
+        let metadata = |entries: &[(&str, &str)]| -> Metadata {
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        };
+
+        // untagged, version 2 and the first entry's key length, key and value length would read
+        // as the 16 bytes of a 393218 ms TTL
+        let versioned = block
+            .clone()
+            .with_version(2)
+            .with_metadata(metadata(&[("\0\0\0\0\0\0", ""), ("b", "c")]));
+        let with_ttl = block
+            .with_ttl(Duration::from_millis(393_218))
+            .with_metadata(metadata(&[("b", "c")]));
+
+        assert_ne!(versioned.canonical_bytes(), with_ttl.canonical_bytes());
+        assert_ne!(versioned.get_hash(), with_ttl.get_hash());
+    }
+
+    #[test]
+    fn test_metadata_is_hashed_in_key_order() {
+        let block = Block::mine(&Block::genesis(), "Hello world!".to_string());
+        let mut forward = Metadata::new();
+        forward.insert("producer".to_string(), "node-1".to_string());
+        forward.insert("tag".to_string(), "blue".to_string());
+        let mut backward = Metadata::new();
+        backward.insert("tag".to_string(), "blue".to_string());
+        backward.insert("producer".to_string(), "node-1".to_string());
+
+        let tagged = block.clone().with_metadata(forward.clone());
+        assert_eq!(
+            tagged.get_hash(),
+            block.clone().with_metadata(backward).get_hash()
+        );
+        assert_ne!(block.get_hash(), tagged.get_hash());
+        assert!(tagged.has_valid_hash());
+
+        forward.insert("tag".to_string(), "green".to_string());
+        assert_ne!(tagged.get_hash(), block.with_metadata(forward).get_hash());
+    }
+
+    #[test]
+    fn test_payload_length_is_hashed() {
+        // everything after the timestamp, previous hash and payload hash
+        let tail = |payload: &str, version: u16| {
+            let block = Block::mine(&Block::genesis(), payload.to_string()).with_version(version);
+            assert!(block.has_valid_hash());
+            block.canonical_bytes()[80..].to_vec()
+        };
+
+        // "ab" under version 2 and "abV\x02\0" under version 1 used to leave the same bytes
+        assert_eq!(tail("ab", 2), tail("abV\x02\0", 1));
+        assert_ne!(tail("ab", 3), tail("ab\x03\0\0", 3));
+        assert_eq!(
+            [
+                2u64.to_le_bytes().as_slice(),
+                b"ab",
+                &3u16.to_le_bytes(),
+                &[0],
+                &0u32.to_le_bytes()
+            ]
+            .concat(),
+            tail("ab", 3)
+        );
+    }
+
+    #[test]
+    fn test_optional_fields_layout_under_version_3() {
+        let block = Block::mine(&Block::genesis(), "Hello world!".to_string()).with_version(3);
+        let millis = Duration::from_secs(60);
+        let with_ttl = block.clone().with_ttl(millis);
+        let locked = block.clone().with_not_before(UNIX_EPOCH + millis);
+        let mut metadata = Metadata::new();
+        metadata.insert("tag".to_string(), String::new());
+        let tagged = block.clone().with_metadata(metadata);
+
+        let hashes = [&block, &with_ttl, &locked, &tagged].map(Block::get_hash);
+        for (i, hash) in hashes.iter().enumerate() {
+            assert!(hashes[i + 1..].iter().all(|other| other != hash));
+        }
+        // the flags byte follows the version, right after the payload
+        let flags = |block: &Block| block.canonical_bytes()[80 + 8 + 12 + 2];
+        assert_eq!(
+            [0, 0b01, 0b10, 0],
+            [&block, &with_ttl, &locked, &tagged].map(flags)
+        );
+        assert!(with_ttl.has_valid_hash() && locked.has_valid_hash() && tagged.has_valid_hash());
+    }
+
+    #[test]
+    fn test_not_before_is_hashed() {
+        let block = Block::mine(&Block::genesis(), "Hello world!".to_string());
+        let release = UNIX_EPOCH + Duration::from_secs(60);
+        let locked = block.clone().with_not_before(release);
+
+        assert_ne!(block.get_hash(), locked.get_hash());
+        // same number of milliseconds as a TTL instead
+        assert_ne!(
+            locked.get_hash(),
+            block.clone().with_ttl(Duration::from_secs(60)).get_hash()
+        );
+        assert!(locked.is_time_locked(release - Duration::from_millis(1)));
+        assert!(!locked.is_time_locked(release));
+        assert_eq!(Some(locked.clone()), Block::decode(&locked.encode()));
+        assert_eq!(
+            Ok(locked.clone()),
+            Block::from_header_and_payload(locked.header(), "Hello world!".to_string())
+        );
+    }
+
+    #[test]
+    fn test_version_is_hashed() {
+        let block = Block::mine(&Block::genesis(), "Hello world!".to_string());
+        let versioned = block.clone().with_version(2);
+
+        assert_eq!(1, Block::genesis().get_version());
+        assert_eq!(block, block.clone().with_version(1));
+        assert_ne!(block.get_hash(), versioned.get_hash());
+        assert!(versioned.has_valid_hash());
+        assert_eq!(
+            Ok(versioned.clone()),
+            Block::from_header_and_payload(versioned.header(), "Hello world!".to_string())
+        );
+    }
+
+    #[test]
+    fn test_payload_hash_survives_redaction() {
+        let mut block = Block::mine(&Block::genesis(), "Hello world!".to_string());
+        let payload_hash = block.get_payload_hash();
+        assert_eq!(Hash::from_bytes("Hello world!".as_bytes()), payload_hash);
+
+        block.redact();
+        assert_eq!(payload_hash, block.get_payload_hash());
+        assert_eq!(payload_hash, block.header().get_payload_hash());
+        assert!(block.matches_payload("Hello world!"));
+        assert_eq!(Some(block.clone()), Block::decode(&block.encode()));
+    }
+
+    #[test]
+    fn test_payload_hash_detects_swapped_body() {
+        let mut block = Block::mine(&Block::genesis(), "Hello world!".to_string());
+        assert!(!block.matches_payload("Goodbye world!"));
+
+        block.set_payload("Goodbye world!".to_string());
+        assert!(!block.has_valid_hash());
+    }
+
+    #[test]
+    fn test_hash_cache_refreshes_on_payload_change() {
+        let mut block = Block::mine(&Block::genesis(), "Hello world!".to_string());
+        let hash = block.make_hash();
+        assert_eq!(block.get_hash(), hash);
+        assert_eq!(hash, block.make_hash());
+
+        assert!(block.has_valid_hash());
+        block.set_payload("Goodbye world!".to_string());
+        assert_ne!(hash, block.make_hash());
+        assert_eq!(Hash::from_bytes(&block.bytes()), block.make_hash());
+        assert!(!block.has_valid_hash());
+    }
+
+    #[test]
+    fn test_ttl_is_hashed() {
+        let block = Block::mine(&Block::genesis(), "Hello world!".to_string());
+        let short = block.clone().with_ttl(Duration::from_secs(60));
+        let long = block.clone().with_ttl(Duration::from_secs(120));
+
+        assert_ne!(block.get_hash(), short.get_hash());
+        assert_ne!(short.get_hash(), long.get_hash());
+        assert_eq!(
+            Ok(short.clone()),
+            Block::from_header_and_payload(short.header(), "Hello world!".to_string())
+        );
+    }
+
+    #[test]
+    fn test_canonical_bytes() {
+        let block =
+            Block::mine(&Block::genesis(), "payload".to_string()).with_ttl(Duration::from_secs(5));
+        assert_eq!(block.canonical_bytes(), block.bytes());
+        assert_eq!(block.get_hash(), Hash::from_bytes(&block.canonical_bytes()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip_keeps_hashes() {
+        let mut chain = crate::Chain::default();
+        chain.add_block("second block".to_string()).unwrap();
+        chain
+            .add_block_with_ttl("third block".to_string(), Duration::from_millis(1500))
+            .unwrap();
+
+        let json = serde_json::to_string(&chain.get_blocks()).unwrap();
+        let blocks: Vec<Block> = serde_json::from_str(&json).unwrap();
+        assert_eq!(chain.get_blocks(), blocks);
+        assert!(blocks
+            .iter()
+            .all(|block| block.get_hash() == block.make_hash()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_unknown_fields() {
+        let block = Block::mine(&Block::genesis(), "Hello world!".to_string());
+        let json = serde_json::to_string(&block).unwrap();
+        assert_eq!(block, serde_json::from_str::<Block>(&json).unwrap());
+
+        let extended = json.replacen('{', r#"{"nonce":42,"#, 1);
+        assert!(serde_json::from_str::<Block>(&extended).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_keeps_redactions() {
+        let mut block = Block::mine(&Block::genesis(), "Hello world!".to_string());
+        let json = serde_json::to_string(&block).unwrap();
+        assert!(!json.contains("redacted"));
+
+        block.redact();
+        let json = serde_json::to_string(&block).unwrap();
+        assert_eq!(block, serde_json::from_str::<Block>(&json).unwrap());
+
+        // without the flag the marker is checked against the hash like any other payload
+        let unflagged = json.replace(r#","redacted":true"#, "");
+        assert!(serde_json::from_str::<Block>(&unflagged).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_mismatched_hash() {
+        let json = serde_json::to_string(&Block::genesis()).unwrap();
+        let tampered = json.replace("Genesis block", "Tampered block");
+        assert!(serde_json::from_str::<Block>(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_difficulty_target() {
+        assert_eq!(
+            Hash::from_hex(&"f".repeat(64)),
+            Ok(Block::difficulty_target(0))
+        );
+        assert_eq!(
+            Hash::from_hex(&format!("0fff{}", "f".repeat(60))),
+            Ok(Block::difficulty_target(4))
+        );
+        assert_eq!(
+            Hash::from_hex(&format!("0000003f{}", "f".repeat(56))),
+            Ok(Block::difficulty_target(26))
+        );
+        assert!(Block::difficulty_target(256).is_zero());
+        assert!(Block::difficulty_target(1000).is_zero());
+    }
+
+    #[test]
+    fn test_meets_target() {
+        let mut block = Block::genesis();
+        block.hash = Hash::from_hex(&format!("00ff{}", "f".repeat(60))).unwrap();
+
+        assert!(block.meets_target(0));
+        assert!(block.meets_target(8));
+        assert!(!block.meets_target(9));
+    }
 }
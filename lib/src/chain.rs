@@ -1,8 +1,23 @@
-use std::fmt::Display;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs::File,
+    io::{self, BufReader, Read, Write},
+    path::Path,
+    slice,
+    time::{Duration, SystemTime},
+};
 
 use crate::{
-    block::{Block, Payload},
-    hash::Hashable,
+    audit::AuditReport,
+    block::{Block, BlockError, BlockHeader, Payload, LATEST_RULE_VERSION},
+    commitment::ChainCommitment,
+    config::{ChainConfig, ValidationPolicy},
+    hash::Hash,
+    mmr::{Mmr, MmrProof},
+    seen::SeenBlocks,
+    sync::SyncSession,
+    wal::{self, Wal, WalError},
 };
 
 #[cfg(feature = "serde")]
@@ -10,54 +25,815 @@ use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "serde")]
 #[derive(Serialize, Deserialize)]
+#[serde(from = "StoredChain")]
 pub struct Chain {
     blocks: Vec<Block>,
+    #[serde(skip)]
+    config: ChainConfig,
+    #[serde(skip)]
+    wal: Option<Wal>,
+    #[serde(skip)]
+    seen: SeenBlocks,
+    #[serde(skip)]
+    index: HashMap<Hash, u64>,
+    #[serde(skip)]
+    mmr: Mmr,
+    #[serde(skip)]
+    reorg_count: u64,
+    #[serde(skip)]
+    max_reorg_depth_seen: u64,
 }
 
 #[cfg(not(feature = "serde"))]
 pub struct Chain {
     blocks: Vec<Block>,
+    config: ChainConfig,
+    wal: Option<Wal>,
+    seen: SeenBlocks,
+    index: HashMap<Hash, u64>,
+    mmr: Mmr,
+    reorg_count: u64,
+    max_reorg_depth_seen: u64,
+}
+
+/// Serialized form of a [`Chain`], indexed once deserialized.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StoredChain {
+    blocks: Vec<Block>,
+}
+
+#[cfg(feature = "serde")]
+impl From<StoredChain> for Chain {
+    fn from(stored: StoredChain) -> Self {
+        let mut chain = Self {
+            blocks: stored.blocks,
+            ..Self::default()
+        };
+        chain.reindex();
+        chain
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Chain {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn to_json_pretty(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parses a chain written by [`Chain::to_json`] or [`Chain::to_json_pretty`], validating it
+    /// and its checkpoints against the default network. Redactions read back are trusted like
+    /// those of [`Chain::load_from_path`], [`Chain::accept`] still refuses them.
+    pub fn from_json(json: &str) -> Result<Self, JsonError> {
+        let chain: Self = serde_json::from_str(json).map_err(JsonError::Parse)?;
+        chain.validate().map_err(JsonError::InvalidChain)?;
+        chain
+            .verify_checkpoints()
+            .map_err(JsonError::InvalidChain)?;
+        Ok(chain)
+    }
 }
 
 impl Chain {
+    pub fn with_config(config: ChainConfig) -> Self {
+        let mut chain = Self {
+            blocks: vec![config.genesis.clone()],
+            seen: SeenBlocks::new(config.seen_capacity),
+            config,
+            wal: None,
+            index: HashMap::new(),
+            mmr: Mmr::default(),
+            reorg_count: 0,
+            max_reorg_depth_seen: 0,
+        };
+        chain.reindex();
+        chain
+    }
+
+    /// Rebuilds the hash to height index behind [`Chain::get_block_by_hash`] and the MMR behind
+    /// [`Chain::mmr_root`] from the blocks. Appends keep both up to date and bulk operations
+    /// rebuild them on their own; only needed after changing the blocks directly.
+    pub fn reindex(&mut self) {
+        self.index = self
+            .blocks
+            .iter()
+            .zip(0..)
+            .map(|(block, height)| (block.get_hash(), height))
+            .collect();
+        self.mmr = self.blocks.iter().fold(Mmr::default(), |mut mmr, block| {
+            mmr.push(block.get_hash());
+            mmr
+        });
+    }
+
+    /// Starts a chain on the private network identified by `seed`. Chains built from the same
+    /// seed share their genesis block and can be synced with each other.
+    pub fn new_network(seed: &str) -> Self {
+        Self::with_config(ChainConfig {
+            genesis: Block::network_genesis(seed),
+            // the pinned checkpoints belong to the default network
+            checkpoints: vec![],
+            ..ChainConfig::default()
+        })
+    }
+
+    /// Rebuilds the chain from the write-ahead log at `path` and keeps logging new blocks to it
+    /// before they are added, so the tip survives a crash. A missing log starts a new chain.
+    pub fn open_with_wal<P: AsRef<Path>>(path: P) -> Result<Self, WalError> {
+        Self::open_with_wal_and_config(path, ChainConfig::default())
+    }
+
+    /// Like [`Chain::open_with_wal`], for a chain configured with `config`, e.g. one on a
+    /// private network.
+    pub fn open_with_wal_and_config<P: AsRef<Path>>(
+        path: P,
+        config: ChainConfig,
+    ) -> Result<Self, WalError> {
+        let (mut chain, intact_len) = Self::replay(path.as_ref(), config)?;
+        chain.wal = Some(Wal::open(path.as_ref(), intact_len)?);
+        Ok(chain)
+    }
+
+    /// Rebuilds the chain from the write-ahead log at `path` without writing to it.
+    pub fn replay_wal<P: AsRef<Path>>(path: P) -> Result<Self, WalError> {
+        Self::replay_wal_with_config(path, ChainConfig::default())
+    }
+
+    /// Like [`Chain::replay_wal`], for a chain configured with `config`.
+    pub fn replay_wal_with_config<P: AsRef<Path>>(
+        path: P,
+        config: ChainConfig,
+    ) -> Result<Self, WalError> {
+        Ok(Self::replay(path.as_ref(), config)?.0)
+    }
+
+    /// Writes every block to `path`, in the same format as the write-ahead log.
+    pub fn save_to_path<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        // like in the log, genesis is left out as it comes from the configuration
+        Wal::write_snapshot(path.as_ref(), self.blocks.get(1..).unwrap_or(&[]))
+    }
+
+    /// Loads a chain written by [`Chain::save_to_path`], validating it. Like the write-ahead log
+    /// the file is this node's own, so the redactions it holds are trusted.
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<Self, WalError> {
+        Self::load_from_path_with_config(path, ChainConfig::default())
+    }
+
+    /// Like [`Chain::load_from_path`], for a chain configured with `config`.
+    pub fn load_from_path_with_config<P: AsRef<Path>>(
+        path: P,
+        config: ChainConfig,
+    ) -> Result<Self, WalError> {
+        Self::read_streaming(File::open(path)?, config, true)
+    }
+
+    /// Writes every block but genesis to `writer`, in the format read by
+    /// [`Chain::import_streaming`].
+    pub fn export<W: Write>(&self, writer: W) -> io::Result<()> {
+        wal::write_records(writer, self.blocks.get(1..).unwrap_or(&[]))
+    }
+
+    /// Reads a chain from `reader` one block at a time, checking each against the previous one
+    /// as it arrives. A bad block aborts the import right away, and only that block is held on
+    /// top of the chain being built. Redacted blocks count as bad ones, the stream may come from
+    /// anywhere.
+    pub fn import_streaming<R: Read>(reader: R) -> Result<Self, WalError> {
+        Self::import_streaming_with_config(reader, ChainConfig::default())
+    }
+
+    /// Like [`Chain::import_streaming`], checking the blocks against `config`'s genesis and
+    /// checkpoints rather than the default network's.
+    pub fn import_streaming_with_config<R: Read>(
+        reader: R,
+        config: ChainConfig,
+    ) -> Result<Self, WalError> {
+        Self::read_streaming(reader, config, false)
+    }
+
+    fn read_streaming<R: Read>(
+        reader: R,
+        config: ChainConfig,
+        trust_redactions: bool,
+    ) -> Result<Self, WalError> {
+        let mut reader = BufReader::new(reader);
+
+        let mut chain = Self::with_config(config);
+        while let Some(block) = wal::read_record(&mut reader)? {
+            if !trust_redactions {
+                Self::ensure_not_redacted(slice::from_ref(&block))
+                    .map_err(WalError::InvalidChain)?;
+            }
+            chain
+                .validate_extends_tip(&block)
+                .map_err(WalError::InvalidChain)?;
+            chain.blocks.push(block);
+        }
+        chain.verify_checkpoints().map_err(WalError::InvalidChain)?;
+        chain.reindex();
+
+        Ok(chain)
+    }
+
+    fn replay(path: &Path, config: ChainConfig) -> Result<(Self, u64), WalError> {
+        let (blocks, intact_len) = Wal::read(path)?;
+
+        let mut chain = Self::with_config(config);
+        for block in blocks {
+            // after a reorg the logged block points to an ancestor of the tip, the blocks it
+            // replaced have to go again
+            let parent = chain
+                .blocks
+                .iter()
+                .rposition(|candidate| candidate.get_hash() == block.get_prev_hash())
+                .ok_or(WalError::InvalidChain(ValidationError::InvalidPrevHash))?;
+            chain.blocks.truncate(parent + 1);
+            chain.blocks.push(block);
+        }
+        chain.validate().map_err(WalError::InvalidChain)?;
+        chain.reindex();
+
+        Ok((chain, intact_len))
+    }
+
+    // the log holds the first `len` blocks afterwards, genesis left out
+    fn rewrite_wal(&mut self, len: usize) -> io::Result<()> {
+        if let Some(wal) = self.wal.as_mut() {
+            wal.rewrite(self.blocks.get(1..len).unwrap_or(&[]))?;
+        }
+        Ok(())
+    }
+
+    fn log_blocks(&mut self, blocks: &[Block]) -> io::Result<()> {
+        if let Some(wal) = self.wal.as_mut() {
+            blocks.iter().try_for_each(|block| wal.append(block))?;
+        }
+        Ok(())
+    }
+
+    /// Copy of the chain up to and including `height`, to branch off from that point. The copy
+    /// shares the configuration but not the write-ahead log. Unlike the methods cutting the chain
+    /// itself, height 0 is fine: the chain is left alone and genesis is kept in the copy.
+    pub fn clone_truncated(&self, height: u64) -> Option<Chain> {
+        let blocks = self.blocks.get(..=usize::try_from(height).ok()?)?;
+
+        let mut chain = Self::with_config(self.config.clone());
+        chain.blocks = blocks.to_vec();
+        chain.reindex();
+        Some(chain)
+    }
+
+    pub fn get_config(&self) -> &ChainConfig {
+        &self.config
+    }
+
     pub fn add_block(&mut self, payload: Payload) -> Result<(), MiningError> {
-        let block = Block::mine(self.blocks.last().ok_or(MiningError::NoPrev)?, payload);
-        self.blocks.push(block);
+        let block = self.simulate_mine(payload)?;
+        self.push_mined(block)
+    }
+
+    /// Like [`Chain::add_block`], with a payload only meaningful for `ttl`.
+    pub fn add_block_with_ttl(
+        &mut self,
+        payload: Payload,
+        ttl: Duration,
+    ) -> Result<(), MiningError> {
+        let block = self.simulate_mine(payload)?;
+        self.push_mined(block.with_ttl(ttl))
+    }
+
+    /// Mines one block per payload, in order, and appends them all with
+    /// [`Chain::accept_blocks`]: either every payload makes it into the chain or none does.
+    /// Returns the hashes of the new blocks.
+    pub fn add_blocks(&mut self, payloads: Vec<Payload>) -> Result<Vec<Hash>, MiningError> {
+        let mut blocks: Vec<Block> = Vec::with_capacity(payloads.len());
+        for payload in payloads {
+            let prev_hash = match blocks.last() {
+                Some(block) => block.get_hash(),
+                None => self.tip_hash().ok_or(MiningError::NoPrev)?,
+            };
+            let block = Block::try_new(SystemTime::now(), prev_hash, payload)
+                .map_err(MiningError::InvalidBlock)?;
+            blocks.push(block.with_version(self.config.rule_version));
+        }
+
+        let hashes = blocks.iter().map(Block::get_hash).collect();
+        self.accept_blocks(blocks).map_err(MiningError::Invalid)?;
+        Ok(hashes)
+    }
+
+    /// Mines a block on top of the tip, under the configured rule version, without appending it.
+    /// It can be inspected and handed back later with [`Chain::push_validated`], as long as the
+    /// tip didn't move in between.
+    pub fn simulate_mine(&self, payload: Payload) -> Result<Block, MiningError> {
+        let prev_hash = self.tip_hash().ok_or(MiningError::NoPrev)?;
+        let block = Block::try_new(SystemTime::now(), prev_hash, payload)
+            .map_err(MiningError::InvalidBlock)?;
+        Ok(block.with_version(self.config.rule_version))
+    }
+
+    fn push_mined(&mut self, block: Block) -> Result<(), MiningError> {
+        if self.config.strict {
+            self.validate_extends_tip(&block)
+                .map_err(MiningError::Invalid)?;
+        }
+        self.append(block)
+            .map_err(|error| MiningError::WalWrite(error.kind()))
+    }
+
+    /// Appends a block that was already validated by the caller, e.g. one mined elsewhere. It is
+    /// only checked against the tip again in strict mode.
+    pub fn push_validated(&mut self, block: Block) -> Result<(), ValidationError> {
+        if self.config.strict {
+            self.validate_extends_tip(&block)?;
+        }
+        self.append(block)
+            .map_err(|error| ValidationError::WalWrite(error.kind()))
+    }
+
+    /// Appends a block received from a peer on top of the tip. Returns `Ok(false)` without
+    /// validating anything again if the block was already processed recently.
+    pub fn submit_block(&mut self, block: Block) -> Result<bool, ValidationError> {
+        if self.has_seen(&block.get_hash()) {
+            return Ok(false);
+        }
+
+        Self::ensure_not_redacted(std::slice::from_ref(&block))?;
+        self.validate_extends_tip(&block)?;
+        self.append(block)
+            .map_err(|error| ValidationError::WalWrite(error.kind()))?;
+        Ok(true)
+    }
+
+    /// Appends a suffix pulled from a peer in one go: it has to link to the tip and hold together
+    /// internally, or nothing is appended. Returns how many blocks were added.
+    pub fn accept_blocks(&mut self, blocks: Vec<Block>) -> Result<usize, ValidationError> {
+        Self::ensure_not_redacted(&blocks)?;
+        let policy = ValidationPolicy::default();
+        let mut previous = self.tip().ok_or(ValidationError::EmptyChain)?;
+        for block in &blocks {
+            Self::validate_neighbour_block(previous, block, &policy, SystemTime::now())?;
+            previous = block;
+        }
+        self.verify_checkpoints_from(self.len() as u64, &blocks)?;
+
+        self.log_blocks(&blocks)
+            .map_err(|error| ValidationError::WalWrite(error.kind()))?;
+        let added = blocks.len();
+        for block in blocks {
+            self.seen.insert(block.get_hash());
+            self.push_indexed(block);
+        }
+        Ok(added)
+    }
+
+    fn validate_extends_tip(&self, block: &Block) -> Result<(), ValidationError> {
+        Self::validate_neighbour_block(
+            self.tip().ok_or(ValidationError::EmptyChain)?,
+            block,
+            &ValidationPolicy::default(),
+            SystemTime::now(),
+        )
+    }
+
+    fn append(&mut self, block: Block) -> io::Result<()> {
+        self.log_blocks(std::slice::from_ref(&block))?;
+        self.seen.insert(block.get_hash());
+        self.push_indexed(block);
         Ok(())
     }
 
+    // the index and MMR follow the blocks appended on top of the tip, only a reorg rebuilds them
+    fn push_indexed(&mut self, block: Block) {
+        self.index.insert(block.get_hash(), self.len() as u64);
+        self.mmr.push(block.get_hash());
+        self.blocks.push(block);
+    }
+
+    /// Whether a block with this hash was added to the chain recently.
+    pub fn has_seen(&self, hash: &Hash) -> bool {
+        self.seen.contains(hash)
+    }
+
     pub fn get_blocks(&self) -> Vec<Block> {
         self.blocks.clone()
     }
 
+    pub fn genesis_payload(&self) -> Option<&Payload> {
+        self.blocks.first().map(Block::get_payload)
+    }
+
+    /// Human-readable name of the network the chain is on: the genesis payload and the start of
+    /// its hash, e.g. `Genesis block (1a2b3c4d…)`.
+    pub fn network_label(&self) -> String {
+        match self.blocks.first() {
+            Some(genesis) => format!("{} ({})", genesis.get_payload(), genesis.get_hash().short()),
+            None => "unknown".to_string(),
+        }
+    }
+
+    /// Header of every block, genesis first, derived as the iterator advances.
+    pub fn iter_headers(&self) -> impl Iterator<Item = BlockHeader> + '_ {
+        self.blocks.iter().map(Block::header)
+    }
+
+    /// Every header, genesis first, for an archive dropping the payloads, loaded back with
+    /// [`HeaderChain::load`](crate::HeaderChain::load).
+    pub fn archive_headers(&self) -> Vec<BlockHeader> {
+        self.iter_headers().collect()
+    }
+
+    pub fn genesis_hash(&self) -> Option<Hash> {
+        self.blocks.first().map(Block::get_hash)
+    }
+
+    pub fn get_block_by_height(&self, height: u64) -> Option<&Block> {
+        self.blocks.get(usize::try_from(height).ok()?)
+    }
+
+    pub fn get_block_by_hash(&self, hash: &Hash) -> Option<&Block> {
+        self.get_block_by_height(self.height_of(hash)?)
+    }
+
+    /// Height of the block with this hash, if it is part of the chain.
+    pub fn height_of(&self, hash: &Hash) -> Option<u64> {
+        let height = *self.index.get(hash)?;
+        self.get_block_by_height(height)
+            .filter(|block| block.get_hash() == *hash)
+            .map(|_| height)
+    }
+
+    /// Payload of every block from genesis up, for callers after the data rather than the
+    /// blocks. `.skip(1)` leaves genesis out.
+    pub fn payloads(&self) -> impl Iterator<Item = &Payload> + '_ {
+        self.blocks.iter().map(Block::get_payload)
+    }
+
+    /// Every block along with its height, genesis being 0.
+    pub fn iter_with_height(&self) -> impl Iterator<Item = (u64, &Block)> {
+        (0..).zip(&self.blocks)
+    }
+
+    /// Blocks from the one hashing to `from` back to genesis, following `prev_hash` links through
+    /// the hash index rather than trusting the order blocks are stored in. Stops at the first
+    /// block whose predecessor isn't known, right away for an unknown `from`.
+    pub fn walk_back(&self, from: &Hash) -> impl Iterator<Item = &Block> {
+        std::iter::successors(self.get_block_by_hash(from), |block| {
+            self.get_block_by_hash(&block.get_prev_hash())
+        })
+    }
+
+    /// Blocks above `height`, empty when `height` is the tip or past it.
+    pub fn blocks_since(&self, height: u64) -> &[Block] {
+        usize::try_from(height)
+            .ok()
+            .and_then(|height| height.checked_add(1))
+            .and_then(|start| self.blocks.get(start..))
+            .unwrap_or(&[])
+    }
+
+    /// Owned copy of [`Chain::blocks_since`]: the blocks a peer at `height` is missing, ready to
+    /// be sent over.
+    pub fn blocks_after(&self, height: u64) -> Vec<Block> {
+        self.blocks_since(height).to_vec()
+    }
+
+    pub fn tip(&self) -> Option<&Block> {
+        self.blocks.last()
+    }
+
+    /// Hash of the last block, which is what the next block will point to.
+    pub fn tip_hash(&self) -> Option<Hash> {
+        self.tip().map(Block::get_hash)
+    }
+
+    pub fn tip_timestamp(&self) -> Option<SystemTime> {
+        self.tip().map(Block::get_timestamp)
+    }
+
+    /// Whether no block was mined for more than `max_age`. A tip timestamped in the future isn't
+    /// stale, an empty chain is.
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.tip_timestamp().is_none_or(|timestamp| {
+            SystemTime::now()
+                .duration_since(timestamp)
+                .is_ok_and(|age| age > max_age)
+        })
+    }
+
+    /// Commits to the current state of the chain: its genesis, tip and height.
+    pub fn commitment(&self) -> Option<ChainCommitment> {
+        Some(ChainCommitment {
+            genesis_hash: self.genesis_hash()?,
+            tip_hash: self.tip_hash()?,
+            height: self.height(),
+        })
+    }
+
+    /// Checks a commitment received from a remote party against this chain's headers.
+    pub fn verify_commitment(&self, commitment: &ChainCommitment, expected_genesis: Hash) -> bool {
+        let headers: Vec<BlockHeader> = self.iter_headers().collect();
+        commitment.verify(&headers, expected_genesis)
+    }
+
+    /// Heights of the blocks whose payload TTL has elapsed at `now`.
+    pub fn expired_blocks(&self, now: SystemTime) -> Vec<u64> {
+        self.iter_with_height()
+            .filter(|(_, block)| block.is_expired(now))
+            .map(|(height, _)| height)
+            .collect()
+    }
+
+    /// Root of a Merkle Mountain Range over every block hash, committing to the whole chain.
+    pub fn mmr_root(&self) -> Option<Hash> {
+        self.mmr.root()
+    }
+
+    /// Proof that the block at `height` is part of the chain committed to by [`Chain::mmr_root`].
+    pub fn mmr_proof(&self, height: u64) -> Option<MmrProof> {
+        self.mmr.proof(height)
+    }
+
+    /// Checks that `block_hash` is committed to by `root`, without needing the chain itself.
+    pub fn verify_mmr_proof(root: Hash, block_hash: Hash, proof: &MmrProof) -> bool {
+        proof.verify(root, block_hash)
+    }
+
     pub fn len(&self) -> usize {
         self.blocks.len()
     }
 
+    /// Index of the tip block, genesis being at height 0.
+    pub fn height(&self) -> u64 {
+        self.len().saturating_sub(1) as u64
+    }
+
+    /// How many blocks were added on top of the block at `height`, 0 for the tip itself, or
+    /// `None` if there is no block at that height.
+    pub fn confirmations(&self, height: u64) -> Option<u64> {
+        if height >= self.len() as u64 {
+            return None;
+        }
+        Some(self.height().saturating_sub(height))
+    }
+
+    /// Folds over the blocks by reference, oldest first.
+    pub fn fold<B, F: FnMut(B, &Block) -> B>(&self, init: B, f: F) -> B {
+        self.blocks.iter().fold(init, f)
+    }
+
+    /// Replaces the payload of the block at `height` with [`REDACTED_PAYLOAD`](crate::REDACTED_PAYLOAD), keeping its hash
+    /// so the chain still links up. Returns whether there was such a block; genesis can't be
+    /// redacted. The write-ahead log is rewritten so the original payload is gone from the disk
+    /// too.
+    pub fn redact(&mut self, height: u64) -> Result<bool, ValidationError> {
+        Self::ensure_not_genesis(height)?;
+        match usize::try_from(height) {
+            Ok(height) if height < self.blocks.len() => {
+                self.blocks[height].redact();
+                self.rewrite_wal(self.blocks.len())
+                    .map_err(|error| ValidationError::WalWrite(error.kind()))?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    // a redacted block's hash can't be recomputed, so only redactions made here through
+    // `Chain::redact` are trusted, never those of blocks coming from elsewhere
+    fn ensure_not_redacted(blocks: &[Block]) -> Result<(), ValidationError> {
+        if blocks.iter().any(Block::is_redacted) {
+            return Err(ValidationError::InvalidHash);
+        }
+        Ok(())
+    }
+
+    // every method rewriting or dropping blocks already in the chain goes through this, genesis
+    // is what identifies the network
+    fn ensure_not_genesis(height: u64) -> Result<(), ValidationError> {
+        if height == 0 {
+            return Err(ValidationError::GenesisImmutable);
+        }
+        Ok(())
+    }
+
+    /// Graphviz DOT description of the chain, one node per block labeled with its height and
+    /// the start of its hash, and an edge from each block to its predecessor.
+    pub fn export_dot(&self) -> String {
+        let mut dot = String::from("digraph chain {\n");
+        for (height, block) in self.blocks.iter().enumerate() {
+            let hash = block.get_hash();
+            dot += &format!("    \"{hash}\" [label=\"{height}\\n{}\"];\n", hash.short());
+            if height > 0 {
+                dot += &format!("    \"{hash}\" -> \"{}\";\n", block.get_prev_hash());
+            }
+        }
+        dot += "}\n";
+        dot
+    }
+
+    /// One-line overview of the chain, e.g. `height=42 tip=abcd1234… valid=true`, for logs where
+    /// the block-by-block `Display` is too much.
+    pub fn summary(&self) -> String {
+        let tip = self
+            .tip_hash()
+            .map_or_else(|| "none".to_string(), |hash| hash.short());
+        format!(
+            "height={} tip={tip} valid={}",
+            self.height(),
+            self.is_valid()
+        )
+    }
+
+    /// Up to the last `n` blocks, newest first.
+    pub fn last_n(&self, n: usize) -> Vec<&Block> {
+        self.blocks.iter().rev().take(n).collect()
+    }
+
+    /// Blocks matching `pred`, oldest first.
+    pub fn find<F: Fn(&Block) -> bool>(&self, pred: F) -> Vec<&Block> {
+        self.blocks.iter().filter(|block| pred(block)).collect()
+    }
+
+    pub fn total_size_bytes(&self) -> usize {
+        self.blocks.iter().map(Block::size_bytes).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
     pub fn validate(&self) -> Result<(), ValidationError> {
-        if !self
-            .blocks
+        self.validate_with(&ValidationPolicy::default())
+    }
+
+    pub fn validate_with(&self, policy: &ValidationPolicy) -> Result<(), ValidationError> {
+        self.validate_blocks(&self.blocks, policy, SystemTime::now())
+    }
+
+    /// [`Chain::validate`] as of `now` instead of the current time, deciding which time-locked
+    /// blocks are released (see [`Block::with_not_before`]) and how far in the future a
+    /// timestamp can be.
+    pub fn validate_at(&self, now: SystemTime) -> Result<(), ValidationError> {
+        self.validate_blocks(&self.blocks, &ValidationPolicy::default(), now)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Checks the chain starts with `expected`, e.g. a genesis the operator trusts, before
+    /// handing a downloaded chain to [`Chain::accept`] or an import.
+    pub fn verify_genesis_against(&self, expected: &Block) -> Result<(), ValidationError> {
+        let genesis = self.blocks.first().ok_or(ValidationError::EmptyChain)?;
+        if genesis != expected {
+            return Err(ValidationError::BadGenesisBlock);
+        }
+        Ok(())
+    }
+
+    fn validate_blocks(
+        &self,
+        blocks: &[Block],
+        policy: &ValidationPolicy,
+        now: SystemTime,
+    ) -> Result<(), ValidationError> {
+        if !blocks
             .first()
             .ok_or(ValidationError::EmptyChain)?
-            .eq(&Block::genesis())
+            .eq(&self.config.genesis)
         {
             return Err(ValidationError::BadGenesisBlock);
         }
 
-        self.blocks
-            .windows(2)
-            .try_for_each(|blocks| Self::validate_neighbour_block(&blocks[0], &blocks[1]))?;
+        blocks.windows(2).try_for_each(|blocks| {
+            Self::validate_neighbour_block(&blocks[0], &blocks[1], policy, now)
+        })?;
 
         Ok(())
     }
 
-    fn validate_neighbour_block(previous: &Block, current: &Block) -> Result<(), ValidationError> {
+    /// Checks the whole chain without stopping at the first problem, returning every offending
+    /// height along with the error found there. An empty result means the chain is valid.
+    pub fn validate_all(&self) -> Vec<(u64, ValidationError)> {
+        let policy = ValidationPolicy::default();
+        let now = SystemTime::now();
+        let mut errors = vec![];
+
+        match self.blocks.first() {
+            None => return vec![(0, ValidationError::EmptyChain)],
+            Some(genesis) if !genesis.eq(&self.config.genesis) => {
+                errors.push((0, ValidationError::BadGenesisBlock))
+            }
+            _ => {}
+        }
+
+        errors.extend(
+            self.blocks
+                .windows(2)
+                .enumerate()
+                .filter_map(|(index, blocks)| {
+                    Self::validate_neighbour_block(&blocks[0], &blocks[1], &policy, now)
+                        .err()
+                        .map(|error| (index as u64 + 1, error))
+                }),
+        );
+
+        errors
+    }
+
+    /// Salvages the valid part of a corrupted chain: drops every block from the first one
+    /// [`Chain::validate_all`] reports a broken hash or link for and returns them. Blocks only
+    /// failing the time checks, e.g. still time-locked ones, aren't corrupted and are kept. Fails
+    /// without touching anything if genesis itself is bad. The write-ahead log is rewritten so
+    /// the dropped blocks don't come back on replay.
+    pub fn drain_invalid_suffix(&mut self) -> Result<Vec<Block>, ValidationError> {
+        let corrupted = self.validate_all().into_iter().find(|(_, error)| {
+            matches!(
+                error,
+                ValidationError::EmptyChain
+                    | ValidationError::BadGenesisBlock
+                    | ValidationError::InvalidHash
+                    | ValidationError::InvalidPrevHash
+            )
+        });
+        let first_invalid = match corrupted {
+            None => return Ok(vec![]),
+            Some((0, error)) => return Err(error),
+            Some((height, _)) => height as usize,
+        };
+        self.rewrite_wal(first_invalid)
+            .map_err(|error| ValidationError::WalWrite(error.kind()))?;
+        let removed = self.blocks.split_off(first_invalid);
+        // they may come in again once fixed
+        removed
+            .iter()
+            .for_each(|block| self.seen.remove(&block.get_hash()));
+        self.reindex();
+        Ok(removed)
+    }
+
+    /// Runs every integrity check over the whole chain and gathers what each one found, see
+    /// [`AuditReport`]. Timestamps are held to the default [`ValidationPolicy`].
+    pub fn audit(&self) -> AuditReport {
+        let policy = ValidationPolicy::default();
+        let now = SystemTime::now();
+        let mut report = AuditReport {
+            genesis_matches: self.blocks.first() == Some(&self.config.genesis),
+            ..AuditReport::default()
+        };
+
+        for (blocks, height) in self.blocks.windows(2).zip(1..) {
+            let (previous, current) = (&blocks[0], &blocks[1]);
+            if previous.get_hash() != current.get_prev_hash() {
+                report.broken_links.push(height);
+            }
+            if !current.has_valid_hash() {
+                report.invalid_hashes.push(height);
+            }
+            let max_drift = policy.max_future_drift_for(current.get_version());
+            if current.get_timestamp() < previous.get_timestamp()
+                || max_drift.is_some_and(|drift| current.get_timestamp() > now + drift)
+            {
+                report.timestamp_anomalies.push(height);
+            }
+        }
+
+        report
+    }
+
+    fn validate_neighbour_block(
+        previous: &Block,
+        current: &Block,
+        policy: &ValidationPolicy,
+        now: SystemTime,
+    ) -> Result<(), ValidationError> {
         if previous.get_hash() != current.get_prev_hash() {
             return Err(ValidationError::InvalidPrevHash);
         }
-        if current.get_hash() != current.make_hash() {
+        if !current.has_valid_hash() {
             return Err(ValidationError::InvalidHash);
         }
+        if current.get_version() > LATEST_RULE_VERSION {
+            return Err(ValidationError::UnsupportedVersion {
+                version: current.get_version(),
+            });
+        }
+        if policy.monotonic_timestamps && current.get_timestamp() < previous.get_timestamp() {
+            return Err(ValidationError::NonMonotonicTimestamp);
+        }
+        if let Some(max_drift) = policy.max_future_drift_for(current.get_version()) {
+            if current.get_timestamp() > now + max_drift {
+                return Err(ValidationError::FutureTimestamp);
+            }
+        }
+        if current.is_time_locked(now) {
+            return Err(ValidationError::TimeLocked);
+        }
         Ok(())
     }
 
@@ -67,65 +843,1363 @@ impl Chain {
             return Ok(());
         }
 
-        other.validate()?;
+        // the incoming chain is checked against our own genesis, not whatever it was built with
+        self.validate_blocks(
+            &other.blocks,
+            &ValidationPolicy::default(),
+            SystemTime::now(),
+        )?;
+        self.verify_checkpoints_of(&other.blocks)?;
+
+        // the incoming chain may have diverged from ours, everything after the last shared block
+        // gets replaced, as long as this doesn't rewrite too much of our history
+        let shared = self.shared_prefix_len(&other);
+        Self::ensure_not_redacted(&other.blocks[shared..])?;
+        // nothing shared means our own genesis isn't the configured one
+        let fork_height = (shared as u64)
+            .checked_sub(1)
+            .ok_or(ValidationError::BadGenesisBlock)?;
+        let depth = self.height() - fork_height;
+        if depth > self.config.max_reorg_depth {
+            return Err(ValidationError::ReorgTooDeep { depth });
+        }
 
         // if we get here we have validated that the incoming chain is alright, so we can append what we are missing
-        let mut missing_blocks: Vec<Block> = other.blocks.into_iter().skip(self.len()).collect();
-        println!("appending new blocks to chain :");
-        missing_blocks.iter().for_each(|block| {
-            println!("{block}");
-        });
-        self.blocks.append(&mut missing_blocks);
+        let mut missing_blocks: Vec<Block> = other.blocks.into_iter().skip(shared).collect();
+        if depth > 0 {
+            self.reorg_count += 1;
+            self.max_reorg_depth_seen = self.max_reorg_depth_seen.max(depth);
+        }
+        self.log_blocks(&missing_blocks)
+            .map_err(|error| ValidationError::WalWrite(error.kind()))?;
+        // the blocks replaced may be submitted again
+        self.blocks[shared..]
+            .iter()
+            .for_each(|block| self.seen.remove(&block.get_hash()));
+        missing_blocks
+            .iter()
+            .for_each(|block| self.seen.insert(block.get_hash()));
+        if depth == 0 {
+            missing_blocks
+                .into_iter()
+                .for_each(|block| self.push_indexed(block));
+        } else {
+            self.blocks.truncate(shared);
+            self.blocks.append(&mut missing_blocks);
+            self.reindex();
+        }
         Ok(())
     }
-}
 
-impl Default for Chain {
-    fn default() -> Self {
-        Self {
-            blocks: vec![Block::genesis()],
+    /// Replaces every block from `from_height` up with `new_blocks`, which have to link to the
+    /// block below and hold together, and returns the blocks taken out. Like [`Chain::accept`],
+    /// it won't replace more than [`ChainConfig::max_reorg_depth`] blocks below the tip, nor
+    /// touch genesis, nor leave fewer than [`ChainConfig::min_len`] blocks.
+    pub fn replace_range(
+        &mut self,
+        from_height: u64,
+        new_blocks: Vec<Block>,
+    ) -> Result<Vec<Block>, ValidationError> {
+        Self::ensure_not_genesis(from_height)?;
+        let fork_height = from_height - 1;
+        let mut previous = self
+            .get_block_by_height(fork_height)
+            .ok_or(ValidationError::InvalidPrevHash)?;
+        let depth = self.height() - fork_height;
+        if depth > self.config.max_reorg_depth {
+            return Err(ValidationError::ReorgTooDeep { depth });
+        }
+        let len = from_height as usize + new_blocks.len();
+        if len < self.config.min_len {
+            return Err(ValidationError::TooShort { len });
+        }
+
+        Self::ensure_not_redacted(&new_blocks)?;
+        let policy = ValidationPolicy::default();
+        for block in &new_blocks {
+            Self::validate_neighbour_block(previous, block, &policy, SystemTime::now())?;
+            previous = block;
+        }
+        self.verify_checkpoints_from(from_height, &new_blocks)?;
+
+        if new_blocks.is_empty() {
+            // no new block pointing below the removed ones, they'd come back on replay
+            self.rewrite_wal(from_height as usize)
+        } else {
+            self.log_blocks(&new_blocks)
         }
+        .map_err(|error| ValidationError::WalWrite(error.kind()))?;
+        self.blocks[from_height as usize..]
+            .iter()
+            .for_each(|block| self.seen.remove(&block.get_hash()));
+        new_blocks
+            .iter()
+            .for_each(|block| self.seen.insert(block.get_hash()));
+        if depth > 0 {
+            self.reorg_count += 1;
+            self.max_reorg_depth_seen = self.max_reorg_depth_seen.max(depth);
+        }
+        let removed = self.blocks.split_off(from_height as usize);
+        if removed.is_empty() {
+            new_blocks
+                .into_iter()
+                .for_each(|block| self.push_indexed(block));
+        } else {
+            self.blocks.extend(new_blocks);
+            self.reindex();
+        }
+        Ok(removed)
     }
-}
 
-impl Display for Chain {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.blocks
+    /// How many times [`Chain::accept`] replaced blocks below the tip since the chain was
+    /// created or loaded.
+    pub fn reorg_count(&self) -> u64 {
+        self.reorg_count
+    }
+
+    /// Deepest reorg counted by [`Chain::reorg_count`], 0 if there was none.
+    pub fn max_reorg_depth_seen(&self) -> u64 {
+        self.max_reorg_depth_seen
+    }
+
+    /// Accepts the longest of `candidates` that passes [`Chain::accept`], falling back to
+    /// shorter ones when it is rejected, and returns how many blocks the chain gained. Candidates
+    /// no longer than the chain are ignored. Fails with the first rejection if every longer
+    /// candidate was rejected.
+    pub fn try_accept_many(
+        &mut self,
+        mut candidates: Vec<Chain>,
+    ) -> Result<usize, ValidationError> {
+        let len = self.len();
+        candidates.retain(|candidate| candidate.len() > len);
+        candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.len()));
+
+        let mut first_error = None;
+        for candidate in candidates {
+            match self.accept(candidate) {
+                Ok(()) => return Ok(self.len() - len),
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+
+        first_error.map_or(Ok(0), Err)
+    }
+
+    /// Confirms the blocks are the ones `headers` describe, height by height, once the bodies of
+    /// an already validated header chain are downloaded. Each block must carry the same header
+    /// fields and actually hash to the header's hash. Fails with the first height that differs,
+    /// including the first height only one of the two holds.
+    pub fn verify_against_headers(&self, headers: &[BlockHeader]) -> Result<(), ValidationError> {
+        let mismatch = self
+            .blocks
             .iter()
-            .try_for_each(|block| writeln!(f, "{}", block))?;
+            .zip(headers)
+            .position(|(block, header)| block.header() != *header || !block.has_valid_hash())
+            .or_else(|| {
+                (self.blocks.len() != headers.len()).then(|| self.blocks.len().min(headers.len()))
+            });
+
+        match mismatch {
+            Some(height) => Err(ValidationError::HeaderMismatch {
+                height: height as u64,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Confirms the blocks at the configured checkpoint heights are the pinned ones. Checkpoints
+    /// above the tip aren't reached yet and pass.
+    pub fn verify_checkpoints(&self) -> Result<(), ValidationError> {
+        self.verify_checkpoints_of(&self.blocks)
+    }
+
+    /// Binary searches `checkpoints`, trusted `(height, hash)` pairs, for the lowest height whose
+    /// block either isn't the pinned one or doesn't hash to its stored hash. A block hash commits
+    /// to all of its history, so once a checkpoint diverges every later one does too and only
+    /// O(log n) blocks get hashed. Checkpoints above the tip are ignored. A payload edited in
+    /// place below a checkpoint that still holds breaks that assumption and may be missed,
+    /// [`Chain::validate_all`] finds those.
+    pub fn bisect_invalid(&self, checkpoints: &[(u64, Hash)]) -> Option<u64> {
+        let mut checkpoints: Vec<(u64, Hash)> = checkpoints
+            .iter()
+            .copied()
+            .filter(|&(height, _)| height <= self.height())
+            .collect();
+        checkpoints.sort_unstable_by_key(|&(height, _)| height);
+
+        let holds = |&(height, hash): &(u64, Hash)| {
+            self.get_block_by_height(height)
+                .is_some_and(|block| block.get_hash() == hash && block.has_valid_hash())
+        };
+        let first_diverging = checkpoints.partition_point(holds);
+        checkpoints.get(first_diverging).map(|&(height, _)| height)
+    }
+
+    fn verify_checkpoints_of(&self, blocks: &[Block]) -> Result<(), ValidationError> {
+        self.verify_checkpoints_from(0, blocks)
+    }
+
+    // `blocks` being the ones at heights `start` and up
+    fn verify_checkpoints_from(&self, start: u64, blocks: &[Block]) -> Result<(), ValidationError> {
+        self.config
+            .checkpoints
+            .iter()
+            .try_for_each(|&(height, hash)| {
+                let block = height
+                    .checked_sub(start)
+                    .and_then(|i| usize::try_from(i).ok())
+                    .and_then(|i| blocks.get(i));
+                match block {
+                    Some(block) if block.get_hash() != hash => {
+                        Err(ValidationError::CheckpointMismatch { height })
+                    }
+                    _ => Ok(()),
+                }
+            })
+    }
+
+    /// Starts a step-by-step sync with a peer, see [`SyncSession`].
+    pub fn sync_session(&self) -> SyncSession {
+        SyncSession::new(self.genesis_hash())
+    }
+
+    /// Whether both chains hold the same blocks from genesis up to and including `up_to`. Heights
+    /// past the end of either chain never agree.
+    pub fn agrees_with(&self, other: &Chain, up_to: u64) -> bool {
+        self.shared_prefix_len(other) as u64 > up_to
+    }
+
+    /// Highest height at which the chain and `remote_headers` hold the same block, found walking
+    /// back from the lower of the two tips. A block hash commits to everything below it, so both
+    /// agree all the way down from there. `None` if not even genesis is shared.
+    pub fn fork_point(&self, remote_headers: &[BlockHeader]) -> Option<u64> {
+        self.blocks
+            .iter()
+            .zip(remote_headers)
+            .rposition(|(block, header)| block.get_hash() == header.get_hash())
+            .map(|height| height as u64)
+    }
+
+    fn shared_prefix_len(&self, other: &Chain) -> usize {
+        self.blocks
+            .iter()
+            .zip(other.blocks.iter())
+            .take_while(|(ours, theirs)| ours.get_hash() == theirs.get_hash())
+            .count()
+    }
+}
+
+impl Default for Chain {
+    fn default() -> Self {
+        Self::with_config(ChainConfig::default())
+    }
+}
+
+impl Display for Chain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.blocks
+            .iter()
+            .try_for_each(|block| writeln!(f, "{}", block))?;
+        Ok(())
+    }
+}
+
+/// Hash of the genesis block of the default network.
+pub fn canonical_genesis_hash() -> Hash {
+    Block::genesis().get_hash()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MiningError {
+    NoPrev,
+    InvalidBlock(BlockError),
+    Invalid(ValidationError),
+    WalWrite(io::ErrorKind),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    EmptyChain,
+    BadGenesisBlock,
+    InvalidHash,
+    InvalidPrevHash,
+    InvalidBlock(BlockError),
+    NonMonotonicTimestamp,
+    FutureTimestamp,
+    ReorgTooDeep { depth: u64 },
+    CheckpointMismatch { height: u64 },
+    HeaderMismatch { height: u64 },
+    UnsupportedVersion { version: u16 },
+    GenesisImmutable,
+    TimeLocked,
+    TooShort { len: usize },
+    WalWrite(io::ErrorKind),
+}
+
+/// Errors reading a chain from JSON with [`Chain::from_json`].
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub enum JsonError {
+    Parse(serde_json::Error),
+    InvalidChain(ValidationError),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use crate::{
+        block::{
+            BlockError, BlockHeader, LATEST_RULE_VERSION, MAX_PAYLOAD_BYTES, REDACTED_PAYLOAD,
+        },
+        chain::{canonical_genesis_hash, ValidationError},
+        hash::Hash,
+        Block, Chain, ChainConfig, MiningError, ValidationPolicy,
+    };
+
+    #[test]
+    fn test_add_block() {
+        let mut chain = Chain::default();
+        assert_eq!(1, chain.blocks.len());
+
+        chain.add_block("Hello world!".to_string()).unwrap();
+        assert_eq!(2, chain.blocks.len());
+    }
+
+    #[test]
+    fn test_genesis_hash() {
+        let chain = Chain::default();
+        assert_eq!(Some(Block::genesis().get_hash()), chain.genesis_hash());
+        assert_eq!(Some(canonical_genesis_hash()), chain.genesis_hash());
+
+        let chain = Chain::new_network("private net");
+        assert_ne!(Some(canonical_genesis_hash()), chain.genesis_hash());
+    }
+
+    #[test]
+    fn test_get_block_by_height() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+
+        assert_eq!(Some(&Block::genesis()), chain.get_block_by_height(0));
+        assert_eq!(
+            "second block",
+            chain.get_block_by_height(1).unwrap().get_payload()
+        );
+        assert_eq!(None, chain.get_block_by_height(2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter_with_height() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        let pairs: Vec<(u64, &Block)> = chain.iter_with_height().collect();
+        assert_eq!((0, &Block::genesis()), pairs[0]);
+        assert_eq!(
+            vec![0, 1, 2],
+            pairs.iter().map(|(height, _)| *height).collect::<Vec<_>>()
+        );
+        assert!(pairs
+            .iter()
+            .all(|&(height, block)| chain.height_of(&block.get_hash()) == Some(height)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_payloads() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        assert_eq!(
+            vec!["Genesis block", "second block", "third block"],
+            chain.payloads().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["second block", "third block"],
+            chain.payloads().skip(1).collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_tip_hash() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        assert_eq!(Some(Block::genesis().get_hash()), chain.tip_hash());
+
+        chain.add_block("second block".to_string())?;
+        assert_eq!(Some(chain.blocks[1].get_hash()), chain.tip_hash());
+        assert_eq!(chain.tip_hash(), chain.blocks.last().map(Block::get_hash));
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_stale() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        let max_age = Duration::from_secs(600);
+        assert_eq!(Some(UNIX_EPOCH), chain.tip_timestamp());
+        assert!(chain.is_stale(max_age));
+
+        chain.add_block("second block".to_string())?;
+        assert_eq!(Some(chain.blocks[1].get_timestamp()), chain.tip_timestamp());
+        assert!(!chain.is_stale(max_age));
+        Ok(())
+    }
+
+    #[test]
+    fn test_simulate_mine() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+
+        let block = chain.simulate_mine("third block".to_string())?;
+        assert_eq!(2, chain.len());
+        assert_eq!(chain.tip_hash(), Some(block.get_prev_hash()));
+
+        chain.push_validated(block).unwrap();
+        assert_eq!(3, chain.len());
+        assert_eq!(Ok(()), chain.validate());
+        Ok(())
+    }
+
+    #[test]
+    fn test_strict_push_validated() {
+        let mut chain = Chain::with_config(ChainConfig {
+            strict: true,
+            ..ChainConfig::default()
+        });
+        let mut block = Block::mine(&chain.blocks[0], "second block".to_string());
+        block.set_payload("tampered payload".to_string());
+
+        assert_eq!(
+            Err(ValidationError::InvalidHash),
+            chain.push_validated(block)
+        );
+        assert_eq!(1, chain.len());
+
+        let block = Block::mine(&chain.blocks[0], "second block".to_string());
+        assert_eq!(Ok(()), chain.push_validated(block));
+        assert_eq!(2, chain.len());
+    }
+
+    #[test]
+    fn test_lenient_push_validated() {
+        let mut chain = Chain::default();
+        let mut block = Block::mine(&chain.blocks[0], "second block".to_string());
+        block.set_payload("tampered payload".to_string());
+
+        assert_eq!(Ok(()), chain.push_validated(block));
+        assert_eq!(Err(ValidationError::InvalidHash), chain.validate());
+    }
+
+    #[test]
+    fn test_accept_blocks() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        let mut peer = Chain::default();
+        for i in 1..4 {
+            peer.add_block(format!("block {i}"))?;
+        }
+
+        assert_eq!(Ok(2), chain.accept_blocks(peer.blocks[1..3].to_vec()));
+        assert_eq!(Ok(1), chain.accept_blocks(peer.blocks[3..].to_vec()));
+        assert_eq!(Ok(0), chain.accept_blocks(vec![]));
+        assert_eq!(peer.blocks, chain.blocks);
+        assert_eq!(Some(3), chain.height_of(&peer.tip_hash().unwrap()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_blocks() {
+        let mut chain = Chain::default();
+        let hashes = chain
+            .add_blocks(vec!["second block".to_string(), "third block".to_string()])
+            .unwrap();
+
+        assert_eq!(3, chain.len());
+        assert_eq!(Some(&hashes[1]), chain.tip_hash().as_ref());
+        assert_eq!(Ok(()), chain.validate());
+
+        let too_large = "x".repeat(MAX_PAYLOAD_BYTES + 1);
+        assert_eq!(
+            Err(MiningError::InvalidBlock(BlockError::PayloadTooLarge {
+                size: MAX_PAYLOAD_BYTES + 1
+            })),
+            chain.add_blocks(vec!["fourth block".to_string(), too_large])
+        );
+        assert_eq!(3, chain.len());
+    }
+
+    #[test]
+    fn test_accept_blocks_broken_link() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        let mut peer = Chain::default();
+        for i in 1..4 {
+            peer.add_block(format!("block {i}"))?;
+        }
+
+        let mut suffix = peer.blocks[1..].to_vec();
+        suffix.remove(1);
+        assert_eq!(
+            Err(ValidationError::InvalidPrevHash),
+            chain.accept_blocks(suffix)
+        );
+        assert_eq!(1, chain.len());
+        assert!(!chain.has_seen(&peer.blocks[1].get_hash()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit_block_dedup() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        let block = Block::mine(&chain.blocks[0], "second block".to_string());
+
+        assert!(!chain.has_seen(&block.get_hash()));
+        assert_eq!(Ok(true), chain.submit_block(block.clone()));
+        assert!(chain.has_seen(&block.get_hash()));
+        assert_eq!(Ok(false), chain.submit_block(block));
+        assert_eq!(2, chain.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit_block_rejects_unlinked() {
+        let mut chain = Chain::default();
+        let block = Block::new(
+            SystemTime::now(),
+            Hash::default(),
+            "second block".to_string(),
+        );
+
+        assert_eq!(
+            Err(ValidationError::InvalidPrevHash),
+            chain.submit_block(block.clone())
+        );
+        assert!(!chain.has_seen(&block.get_hash()));
+        assert_eq!(1, chain.len());
+    }
+
+    #[test]
+    fn test_verify_commitment() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        let commitment = chain.commitment().unwrap();
+        assert_eq!(2, commitment.height);
+        assert!(chain.verify_commitment(&commitment, Block::genesis().get_hash()));
+        assert!(!chain.verify_commitment(&commitment, Hash::default()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_tampered_commitment() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        let mut commitment = chain.commitment().unwrap();
+        commitment.tip_hash = chain.blocks[1].get_hash();
+        assert!(!chain.verify_commitment(&commitment, Block::genesis().get_hash()));
+
+        let mut commitment = chain.commitment().unwrap();
+        commitment.height = 3;
+        assert!(!chain.verify_commitment(&commitment, Block::genesis().get_hash()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_expired_blocks() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block_with_ttl("ephemeral".to_string(), Duration::from_secs(10))?;
+        chain.add_block("permanent".to_string())?;
+        chain.add_block_with_ttl("longer lived".to_string(), Duration::from_secs(60))?;
+
+        let mined_at = chain.blocks[3].get_timestamp();
+        assert!(chain.expired_blocks(mined_at).is_empty());
+        assert_eq!(
+            vec![1],
+            chain.expired_blocks(mined_at + Duration::from_secs(30))
+        );
+        assert_eq!(
+            vec![1, 3],
+            chain.expired_blocks(mined_at + Duration::from_secs(90))
+        );
+        assert!(chain.is_valid());
+        Ok(())
+    }
+
+    #[test]
+    fn test_clone_truncated() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+        chain.add_block("fourth block".to_string())?;
+        chain.add_block("fifth block".to_string())?;
+
+        let mut fork = chain.clone_truncated(2).unwrap();
+        assert_eq!(3, fork.len());
+        fork.add_block("forked fourth block".to_string())?;
+
+        assert_eq!(Ok(()), fork.validate());
+        assert_eq!("forked fourth block", fork.blocks[3].get_payload());
+        assert_eq!(5, chain.len());
+        assert_eq!("fourth block", chain.blocks[3].get_payload());
+        Ok(())
+    }
+
+    #[test]
+    fn test_clone_truncated_out_of_range() {
+        let chain = Chain::default();
+
+        assert!(chain.clone_truncated(0).is_some());
+        assert!(chain.clone_truncated(1).is_none());
+    }
+
+    #[test]
+    fn test_mmr_proofs() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        for i in 1..7 {
+            chain.add_block(format!("block {i}"))?;
+        }
+
+        let root = chain.mmr_root().unwrap();
+        for (height, block) in chain.blocks.iter().enumerate() {
+            let proof = chain.mmr_proof(height as u64).unwrap();
+            assert!(Chain::verify_mmr_proof(root, block.get_hash(), &proof));
+        }
+        assert!(chain.mmr_proof(7).is_none());
+
+        chain.add_block("block 7".to_string())?;
+        assert_ne!(root, chain.mmr_root().unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mmr_follows_appends_and_reorgs() -> Result<(), MiningError> {
+        // a copy rebuilds its MMR from scratch
+        let rebuilt = |chain: &Chain| chain.clone_truncated(chain.height()).unwrap().mmr_root();
+        let mut chain = Chain::default();
+        let mut peer = Chain::default();
+        for i in 1..5 {
+            peer.add_block(format!("block {i}"))?;
+        }
+
+        chain.add_block("local block".to_string())?;
+        assert_eq!(rebuilt(&chain), chain.mmr_root());
+        chain.accept(peer.clone_truncated(2).unwrap()).unwrap();
+        assert_eq!(rebuilt(&chain), chain.mmr_root());
+        chain.accept(peer.clone_truncated(3).unwrap()).unwrap();
+        assert_eq!(rebuilt(&chain), chain.mmr_root());
+        chain.accept_blocks(peer.blocks[4..].to_vec()).unwrap();
+        assert_eq!(peer.mmr_root(), chain.mmr_root());
+
+        let mut fork = chain.clone_truncated(2).unwrap();
+        fork.add_block("other block 3".to_string())?;
+        chain.replace_range(3, fork.blocks[3..].to_vec()).unwrap();
+        assert_eq!(fork.mmr_root(), chain.mmr_root());
+        chain.replace_range(3, vec![]).unwrap();
+        chain.add_block("block 3".to_string())?;
+        assert_eq!(rebuilt(&chain), chain.mmr_root());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mmr_forged_proof() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        for i in 1..5 {
+            chain.add_block(format!("block {i}"))?;
+        }
+
+        let root = chain.mmr_root().unwrap();
+        let proof = chain.mmr_proof(2).unwrap();
+        assert!(!Chain::verify_mmr_proof(
+            root,
+            chain.blocks[3].get_hash(),
+            &proof
+        ));
+
+        let mut forged = proof.clone();
+        forged.path[0] = Hash::default();
+        assert!(!Chain::verify_mmr_proof(
+            root,
+            chain.blocks[2].get_hash(),
+            &forged
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_block_payload_too_large() {
+        let mut chain = Chain::default();
+        let payload = "a".repeat(MAX_PAYLOAD_BYTES + 1);
+
+        assert!(matches!(
+            chain.add_block(payload),
+            Err(MiningError::InvalidBlock(
+                BlockError::PayloadTooLarge { .. }
+            ))
+        ));
+        assert_eq!(1, chain.len());
+    }
+
+    #[test]
+    fn test_height() {
+        let mut chain = Chain::default();
+        assert_eq!(0, chain.height());
+
+        chain.add_block("second block".to_string()).unwrap();
+        assert_eq!(1, chain.height());
+
+        chain.add_block("third block".to_string()).unwrap();
+        assert_eq!(2, chain.height());
+    }
+
+    #[test]
+    fn test_confirmations() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        assert_eq!(Some(0), chain.confirmations(2));
+        assert_eq!(Some(2), chain.confirmations(0));
+        assert_eq!(None, chain.confirmations(3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fold_payload_bytes() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second".to_string())?;
+        chain.add_block("third".to_string())?;
+
+        let payload_bytes = chain.fold(0, |total, block| total + block.get_payload().len());
+        assert_eq!("Genesis block".len() + 6 + 5, payload_bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_in_timestamp_window() {
+        let mut blocks = vec![Block::genesis()];
+        for secs in [10, 20, 30, 40] {
+            let prev_hash = blocks.last().unwrap().get_hash();
+            blocks.push(Block::new(
+                UNIX_EPOCH + Duration::from_secs(secs),
+                prev_hash,
+                format!("block at {secs}"),
+            ));
+        }
+        let chain = Chain {
+            blocks,
+            ..Chain::default()
+        };
+
+        let window = UNIX_EPOCH + Duration::from_secs(15)..=UNIX_EPOCH + Duration::from_secs(30);
+        let found = chain.find(|block| window.contains(&block.get_timestamp()));
+        assert_eq!(
+            vec!["block at 20", "block at 30"],
+            found
+                .iter()
+                .map(|block| block.get_payload())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_find_by_payload_length() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("short".to_string())?;
+        chain.add_block("a much longer payload".to_string())?;
+
+        let found = chain.find(|block| block.get_payload().len() > 10);
+        assert_eq!(2, found.len());
+        assert_eq!(chain.get_block_by_height(0), Some(found[0]));
+        assert!(chain
+            .find(|block| block.get_payload().is_empty())
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_block_by_hash_after_load() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        for i in 1..10 {
+            chain.add_block(format!("block {i}"))?;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.bin");
+        chain.save_to_path(&path).unwrap();
+
+        let loaded = Chain::load_from_path(&path).unwrap();
+        for block in chain.get_blocks() {
+            assert_eq!(Some(&block), loaded.get_block_by_hash(&block.get_hash()));
+        }
+        assert!(loaded.get_block_by_hash(&Hash::default()).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_block_by_hash_after_reorg() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("replaced block".to_string())?;
+        let replaced = chain.tip_hash().unwrap();
+
+        let mut fork = Chain::default();
+        fork.add_block("fork block".to_string())?;
+        fork.add_block("next fork block".to_string())?;
+        chain.accept(fork).unwrap();
+
+        assert!(chain.get_block_by_hash(&replaced).is_none());
+        let tip = chain.tip().unwrap();
+        assert_eq!(Some(tip), chain.get_block_by_hash(&tip.get_hash()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_blocks_since() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        assert_eq!(&chain.blocks[1..], chain.blocks_since(0));
+        assert_eq!(&chain.blocks[2..], chain.blocks_since(1));
+        assert!(chain.blocks_since(2).is_empty());
+        assert!(chain.blocks_since(u64::MAX).is_empty());
+        assert_eq!(Some(1), chain.height_of(&chain.blocks[1].get_hash()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_height_of() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        assert_eq!(Some(0), chain.height_of(&canonical_genesis_hash()));
+        assert_eq!(Some(2), chain.height_of(&chain.tip_hash().unwrap()));
+        assert_eq!(None, chain.height_of(&Hash::default()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_blocks_after() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        assert_eq!(chain.blocks[1..].to_vec(), chain.blocks_after(0));
+        assert_eq!(chain.blocks[2..].to_vec(), chain.blocks_after(1));
+        assert_eq!(Vec::<Block>::new(), chain.blocks_after(2));
+        assert!(chain.blocks_after(10).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_accept_rejects_checkpoint_mismatch() -> Result<(), MiningError> {
+        let mut honest = Chain::default();
+        honest.add_block("second block".to_string())?;
+        honest.add_block("third block".to_string())?;
+
+        let config = ChainConfig {
+            checkpoints: vec![(2, honest.blocks[2].get_hash())],
+            ..ChainConfig::default()
+        };
+        let mut chain = Chain::with_config(config);
+        assert_eq!(Ok(()), chain.verify_checkpoints());
+
+        let mut tampered = Chain::default();
+        tampered.add_block("second block".to_string())?;
+        tampered.add_block("tampered block".to_string())?;
+        tampered.add_block("fourth block".to_string())?;
+        assert_eq!(
+            Err(ValidationError::CheckpointMismatch { height: 2 }),
+            chain.accept(tampered)
+        );
+        assert_eq!(1, chain.len());
+
+        chain.accept(honest).unwrap();
+        assert_eq!(Ok(()), chain.verify_checkpoints());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bisect_invalid() -> Result<(), MiningError> {
+        let mut honest = Chain::default();
+        for i in 1..10 {
+            honest.add_block(format!("block {i}"))?;
+        }
+        let checkpoints: Vec<(u64, Hash)> = honest
+            .blocks
+            .iter()
+            .zip(0..)
+            .map(|(block, height)| (height, block.get_hash()))
+            .collect();
+        assert_eq!(None, honest.bisect_invalid(&checkpoints));
+
+        // history rewritten from height 6 on
+        let mut tampered = honest.clone_truncated(5).unwrap();
+        for i in 6..10 {
+            tampered.add_block(format!("tampered block {i}"))?;
+        }
+        assert_eq!(Some(6), tampered.bisect_invalid(&checkpoints));
+        assert_eq!(
+            Some(8),
+            tampered.bisect_invalid(&[(8, checkpoints[8].1), (2, checkpoints[2].1)])
+        );
+
+        // a tampered tip still links up but no longer hashes to its stored hash
+        let mut tampered = honest.clone_truncated(9).unwrap();
+        tampered.blocks[9].set_payload("tampered block".to_string());
+        assert_eq!(Some(9), tampered.bisect_invalid(&checkpoints));
+        assert_eq!(None, tampered.bisect_invalid(&checkpoints[..9]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_redact() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("personal data".to_string())?;
+        chain.add_block("third block".to_string())?;
+        let hash = chain.blocks[1].get_hash();
+
+        assert_eq!(Ok(true), chain.redact(1));
+        assert_eq!(Ok(false), chain.redact(3));
+
+        let block = &chain.blocks[1];
+        assert!(block.is_redacted());
+        assert_eq!(REDACTED_PAYLOAD, block.get_payload());
+        assert_eq!(hash, block.get_hash());
+        assert_eq!(Ok(()), chain.validate());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.bin");
+        chain.save_to_path(&path).unwrap();
+        let loaded = Chain::load_from_path(&path).unwrap();
+        assert!(loaded.blocks[1].is_redacted());
+        assert!(loaded
+            .find(|block| block.get_payload() == "personal data")
+            .is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_range() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+        let mut fork = chain.clone_truncated(1).unwrap();
+        fork.add_block("other third block".to_string())?;
+        fork.add_block("fourth block".to_string())?;
+
+        let removed = chain.replace_range(2, fork.blocks[2..].to_vec()).unwrap();
+        assert_eq!(
+            vec!["third block"],
+            removed.iter().map(Block::get_payload).collect::<Vec<_>>()
+        );
+        assert_eq!(fork.blocks, chain.blocks);
+        assert_eq!(Some(3), chain.height_of(&fork.tip_hash().unwrap()));
+        assert_eq!(None, chain.height_of(&removed[0].get_hash()));
+        assert_eq!(1, chain.reorg_count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resubmit_after_reorg() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+        let third = chain.blocks[2].clone();
+        let mut fork = chain.clone_truncated(1).unwrap();
+        fork.add_block("other third block".to_string())?;
+        fork.add_block("fourth block".to_string())?;
+        let other_third = fork.blocks[2].clone();
+
+        chain.accept(fork).unwrap();
+        assert!(!chain.has_seen(&third.get_hash()));
+        chain.replace_range(2, vec![]).unwrap();
+        assert!(!chain.has_seen(&other_third.get_hash()));
+
+        assert_eq!(Ok(true), chain.submit_block(third));
+        chain.replace_range(2, vec![]).unwrap();
+        assert_eq!(Ok(true), chain.submit_block(other_third));
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_range_rejected() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+        let mut fork = chain.clone_truncated(1).unwrap();
+        fork.add_block("other third block".to_string())?;
+        fork.add_block("fourth block".to_string())?;
+        let blocks = chain.blocks.clone();
+
+        let mut broken = fork.blocks[2..].to_vec();
+        broken.remove(0);
+        assert_eq!(
+            Err(ValidationError::InvalidPrevHash),
+            chain.replace_range(2, broken)
+        );
+        assert_eq!(
+            Err(ValidationError::GenesisImmutable),
+            chain.replace_range(0, fork.blocks.clone())
+        );
+        assert_eq!(
+            Err(ValidationError::InvalidPrevHash),
+            chain.replace_range(4, vec![])
+        );
+        assert_eq!(blocks, chain.blocks);
+
+        let mut chain = Chain::with_config(ChainConfig {
+            max_reorg_depth: 1,
+            ..ChainConfig::default()
+        });
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+        assert_eq!(
+            Err(ValidationError::ReorgTooDeep { depth: 2 }),
+            chain.replace_range(1, fork.blocks[1..].to_vec())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_genesis_against() -> Result<(), MiningError> {
+        let mut downloaded = Chain::default();
+        downloaded.add_block("second block".to_string())?;
+
+        assert_eq!(Ok(()), downloaded.verify_genesis_against(&Block::genesis()));
+        assert_eq!(
+            Err(ValidationError::BadGenesisBlock),
+            downloaded.verify_genesis_against(&Block::network_genesis("private net"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_range_min_len() -> Result<(), MiningError> {
+        let mut chain = Chain::with_config(ChainConfig {
+            min_len: 3,
+            ..ChainConfig::default()
+        });
+        for i in 1..4 {
+            chain.add_block(format!("block {i}"))?;
+        }
+
+        assert_eq!(
+            Err(ValidationError::TooShort { len: 2 }),
+            chain.replace_range(2, vec![])
+        );
+        assert_eq!(4, chain.len());
+        assert_eq!(1, chain.replace_range(3, vec![]).unwrap().len());
+        assert_eq!(3, chain.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_range_truncation_survives_replay() -> Result<(), MiningError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.wal");
+
+        let mut chain = Chain::open_with_wal(&path).unwrap();
+        for i in 1..4 {
+            chain.add_block(format!("block {i}"))?;
+        }
+        chain.replace_range(2, vec![]).unwrap();
+        let blocks = chain.get_blocks();
+        drop(chain);
+
+        assert_eq!(blocks, Chain::replay_wal(&path).unwrap().get_blocks());
+        // the log is still appended to after being rewritten
+        let mut chain = Chain::open_with_wal(&path).unwrap();
+        chain.add_block("other block 2".to_string())?;
+        assert_eq!(3, Chain::replay_wal(&path).unwrap().len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_genesis_immutable() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+
+        let other_genesis = Block::network_genesis("private net");
+
+        assert_eq!(Err(ValidationError::GenesisImmutable), chain.redact(0));
+        assert!(!chain.blocks[0].is_redacted());
+        assert_eq!(
+            Err(ValidationError::GenesisImmutable),
+            chain.replace_range(0, vec![other_genesis])
+        );
+        assert_eq!(
+            Err(ValidationError::GenesisImmutable),
+            chain.replace_range(0, vec![])
+        );
+        assert_eq!(2, chain.len());
+        assert_eq!(Some(canonical_genesis_hash()), chain.genesis_hash());
+        Ok(())
+    }
+
+    #[test]
+    fn test_redact_rewrites_wal() -> Result<(), MiningError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.wal");
+
+        let mut chain = Chain::open_with_wal(&path).unwrap();
+        chain.add_block("personal data".to_string())?;
+        chain.add_block("third block".to_string())?;
+        chain.redact(1).unwrap();
+        chain.add_block("fourth block".to_string())?;
+        drop(chain);
+
+        let log = std::fs::read(&path).unwrap();
+        assert!(!log
+            .windows("personal data".len())
+            .any(|window| window == b"personal data"));
+        let replayed = Chain::open_with_wal(&path).unwrap();
+        assert_eq!(4, replayed.len());
+        assert!(replayed.blocks[1].is_redacted());
+        assert_eq!(REDACTED_PAYLOAD, replayed.blocks[1].get_payload());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_redacted_blocks_from_peers() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        let mut peer = Chain::default();
+        peer.add_block("second block".to_string())?;
+        peer.add_block("third block".to_string())?;
+        peer.redact(2).unwrap();
+
+        assert_eq!(
+            Err(ValidationError::InvalidHash),
+            chain.accept(peer.clone_truncated(2).unwrap())
+        );
+        assert_eq!(
+            Err(ValidationError::InvalidHash),
+            chain.accept_blocks(peer.blocks[1..].to_vec())
+        );
+        chain.submit_block(peer.blocks[1].clone()).unwrap();
+        assert_eq!(
+            Err(ValidationError::InvalidHash),
+            chain.submit_block(peer.blocks[2].clone())
+        );
+        assert_eq!(2, chain.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_redacted_block_cannot_change_payload() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.redact(1).unwrap();
+        chain.blocks[1].set_payload("forged payload".to_string());
+
+        assert_eq!(Err(ValidationError::InvalidHash), chain.validate());
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_dot() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        let dot = chain.export_dot();
+        assert!(dot.starts_with("digraph chain {"));
+        assert_eq!(3, dot.matches("[label=").count());
+        assert_eq!(2, dot.matches(" -> ").count());
+        assert!(dot.contains(&format!(
+            "\"{}\" -> \"{}\"",
+            chain.blocks[2].get_hash(),
+            chain.blocks[1].get_hash()
+        )));
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_against_headers() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+        let headers: Vec<BlockHeader> = chain.blocks.iter().map(Block::header).collect();
+
+        assert_eq!(Ok(()), chain.verify_against_headers(&headers));
+        assert_eq!(
+            Err(ValidationError::HeaderMismatch { height: 2 }),
+            chain.verify_against_headers(&headers[..2])
+        );
+
+        chain.blocks[1].set_payload("tampered payload".to_string());
+        assert_eq!(
+            Err(ValidationError::HeaderMismatch { height: 1 }),
+            chain.verify_against_headers(&headers)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_last_n() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        assert_eq!(
+            vec![&chain.blocks[2], &chain.blocks[1], &chain.blocks[0]],
+            chain.last_n(10)
+        );
+        assert_eq!(vec![&chain.blocks[2]], chain.last_n(1));
+        assert!(chain.last_n(0).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_network_label() {
+        let chain = Chain::default();
+        let label = chain.network_label();
+
+        assert_eq!(Some(&"Genesis block".to_string()), chain.genesis_payload());
+        assert!(label.contains("Genesis block"));
+        assert!(label.contains(&canonical_genesis_hash().short()));
+        assert_ne!(label, Chain::new_network("private net").network_label());
+    }
+
+    #[test]
+    fn test_walk_back() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        let walked: Vec<&Block> = chain.walk_back(&chain.tip_hash().unwrap()).collect();
+        assert_eq!(chain.height() + 1, walked.len() as u64);
+        assert_eq!(chain.tip(), walked.first().copied());
+        assert_eq!(Some(&Block::genesis()), walked.last().copied());
+
+        assert_eq!(2, chain.walk_back(&chain.blocks[1].get_hash()).count());
+        assert_eq!(0, chain.walk_back(&Hash::default()).count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter_headers() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        assert_eq!(chain.len(), chain.iter_headers().count());
+        assert!(chain
+            .iter_headers()
+            .zip(chain.blocks.iter())
+            .all(|(header, block)| header.get_hash() == block.get_hash()));
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_round_trip() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        let json = chain.to_json().unwrap();
+        let pretty = chain.to_json_pretty().unwrap();
+        assert!(pretty.lines().count() > json.lines().count());
+        assert_eq!(chain.blocks, Chain::from_json(&json).unwrap().blocks);
+        assert_eq!(chain.blocks, Chain::from_json(&pretty).unwrap().blocks);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_round_trip_keeps_redactions() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+        chain.redact(1).unwrap();
+
+        let loaded = Chain::from_json(&chain.to_json().unwrap()).unwrap();
+        assert_eq!(chain.blocks, loaded.blocks);
+        assert!(loaded.blocks[1].is_redacted());
+
+        // read back, the redacted block still isn't taken from a peer
+        let mut other = Chain::default();
+        assert_eq!(Err(ValidationError::InvalidHash), other.accept(loaded));
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_from_json_rejects_invalid_chain() -> Result<(), MiningError> {
+        let mut chain = Chain::new_network("private net");
+        chain.add_block("second block".to_string())?;
+
+        assert!(matches!(
+            Chain::from_json(&chain.to_json().unwrap()),
+            Err(super::JsonError::InvalidChain(
+                ValidationError::BadGenesisBlock
+            ))
+        ));
+        assert!(matches!(
+            Chain::from_json("{ \"blocks\": "),
+            Err(super::JsonError::Parse(_))
+        ));
+
+        let json = Chain::default().to_json().unwrap();
+        let extended = json.replacen('{', r#"{"network":"main","#, 1);
+        assert!(matches!(
+            Chain::from_json(&extended),
+            Err(super::JsonError::Parse(_))
+        ));
         Ok(())
     }
-}
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum MiningError {
-    NoPrev,
-}
+    #[test]
+    fn test_summary() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum ValidationError {
-    EmptyChain,
-    BadGenesisBlock,
-    InvalidHash,
-    InvalidPrevHash,
-}
+        let summary = chain.summary();
+        assert!(summary.contains("height=2"));
+        assert!(summary.contains(&format!("tip={}", chain.tip_hash().unwrap().short())));
+        assert!(summary.ends_with("valid=true"));
+        assert_eq!(1, summary.lines().count());
+        Ok(())
+    }
 
-#[cfg(test)]
-mod tests {
-    use std::time::SystemTime;
+    #[test]
+    fn test_reindex() {
+        let mut blocks = vec![Block::genesis()];
+        blocks.push(Block::mine(&blocks[0], "second block".to_string()));
+        let mut chain = Chain {
+            blocks,
+            ..Chain::default()
+        };
+        let hash = chain.blocks[1].get_hash();
+        assert!(chain.get_block_by_hash(&hash).is_none());
 
-    use crate::{chain::ValidationError, hash::Hash, Block, Chain, MiningError};
+        chain.reindex();
+        assert_eq!(Some(&chain.blocks[1]), chain.get_block_by_hash(&hash));
+    }
 
     #[test]
-    fn test_add_block() {
+    fn test_total_size_bytes() -> Result<(), MiningError> {
         let mut chain = Chain::default();
-        assert_eq!(1, chain.blocks.len());
+        let genesis_size = chain.total_size_bytes();
 
-        chain.add_block("Hello world!".to_string()).unwrap();
-        assert_eq!(2, chain.blocks.len());
+        chain.add_block("second block".to_string())?;
+        assert_eq!(
+            genesis_size + chain.blocks[1].size_bytes(),
+            chain.total_size_bytes()
+        );
+        Ok(())
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_validate_empty_chain() {
         let mut chain = Chain::default();
         chain.blocks = vec![];
@@ -187,6 +2261,260 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_is_valid() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        assert!(chain.is_valid());
+
+        if let Some(x) = chain.blocks.get_mut(1) {
+            x.set_payload("tampered payload".to_string());
+        }
+        assert!(!chain.is_valid());
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_monotonic_timestamps() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+
+        let earlier = chain.blocks[1].get_timestamp() - Duration::from_secs(60);
+        let prev_hash = chain.blocks[1].get_hash();
+        chain
+            .blocks
+            .push(Block::new(earlier, prev_hash, "third block".to_string()));
+
+        let lenient = ValidationPolicy {
+            monotonic_timestamps: false,
+            ..ValidationPolicy::default()
+        };
+        assert_eq!(Ok(()), chain.validate_with(&lenient));
+        assert_eq!(
+            Err(ValidationError::NonMonotonicTimestamp),
+            chain.validate_with(&ValidationPolicy::default())
+        );
+        assert_eq!(
+            Err(ValidationError::NonMonotonicTimestamp),
+            chain.validate()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_with_future_drift() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+
+        let future = SystemTime::now() + Duration::from_secs(24 * 60 * 60);
+        let prev_hash = chain.blocks[0].get_hash();
+        chain
+            .blocks
+            .push(Block::new(future, prev_hash, "second block".to_string()));
+
+        let lenient = ValidationPolicy {
+            max_future_drift: None,
+            ..ValidationPolicy::default()
+        };
+        assert_eq!(Ok(()), chain.validate_with(&lenient));
+        assert_eq!(Err(ValidationError::FutureTimestamp), chain.validate());
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_by_rule_version() -> Result<(), MiningError> {
+        let mut chain = Chain::with_config(ChainConfig {
+            rule_version: 2,
+            ..ChainConfig::default()
+        });
+        chain.add_block("second block".to_string())?;
+        assert_eq!(2, chain.blocks[1].get_version());
+        assert_eq!(1, chain.blocks[0].get_version());
+
+        // an hour ahead is within the v1 tolerance but not the v2 one
+        let ahead = SystemTime::now() + Duration::from_secs(60 * 60);
+        let v1 = Block::new(ahead, chain.blocks[1].get_hash(), "v1 block".to_string());
+        let v2 = v1.clone().with_version(2);
+
+        chain.blocks.push(v1);
+        assert_eq!(Ok(()), chain.validate());
+
+        chain.blocks.pop();
+        chain.blocks.push(v2);
+        assert_eq!(Err(ValidationError::FutureTimestamp), chain.validate());
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_locked_block() {
+        let mut chain = Chain::default();
+        let release = SystemTime::now() + Duration::from_secs(3600);
+        let block =
+            Block::mine(&chain.blocks[0], "sealed bid".to_string()).with_not_before(release);
+        assert!(block.has_valid_hash());
+        chain.blocks.push(block);
+
+        assert_eq!(
+            Err(ValidationError::TimeLocked),
+            chain.validate_at(release - Duration::from_secs(1))
+        );
+        assert_eq!(Err(ValidationError::TimeLocked), chain.validate());
+        assert_eq!(Ok(()), chain.validate_at(release));
+        assert_eq!(
+            Ok(()),
+            chain.validate_at(release + Duration::from_secs(3600))
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_rule_version() {
+        let mut chain = Chain::default();
+        let version = LATEST_RULE_VERSION + 1;
+        let block = Block::mine(&chain.blocks[0], "second block".to_string()).with_version(version);
+
+        assert_eq!(
+            Err(ValidationError::UnsupportedVersion { version }),
+            chain.submit_block(block)
+        );
+        assert_eq!(1, chain.len());
+    }
+
+    #[test]
+    fn test_validate_all_reports_every_error() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+        chain.add_block("fourth block".to_string())?;
+
+        if let Some(x) = chain.blocks.get_mut(1) {
+            x.set_payload("tampered payload".to_string());
+        }
+        if let Some(x) = chain.blocks.get_mut(3) {
+            x.set_payload("tampered payload".to_string());
+        }
+
+        assert_eq!(
+            chain.validate_all(),
+            vec![
+                (1, ValidationError::InvalidHash),
+                (3, ValidationError::InvalidHash)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_drain_invalid_suffix() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        for i in 1..5 {
+            chain.add_block(format!("block {i}"))?;
+        }
+        assert_eq!(Ok(vec![]), chain.drain_invalid_suffix());
+
+        chain.blocks[3].set_payload("tampered payload".to_string());
+        let tip_hash = chain.tip_hash().unwrap();
+        let removed = chain.drain_invalid_suffix().unwrap();
+
+        assert_eq!(3, chain.len());
+        assert_eq!(2, removed.len());
+        assert_eq!("tampered payload", removed[0].get_payload());
+        assert_eq!(Ok(()), chain.validate());
+        assert_eq!(None, chain.height_of(&tip_hash));
+        Ok(())
+    }
+
+    #[test]
+    fn test_drain_invalid_suffix_survives_replay() -> Result<(), MiningError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.wal");
+
+        let mut chain = Chain::open_with_wal(&path).unwrap();
+        for i in 1..5 {
+            chain.add_block(format!("block {i}"))?;
+        }
+        let dropped = chain.blocks[3].clone();
+        chain.blocks[3].set_payload("tampered payload".to_string());
+        chain.drain_invalid_suffix().unwrap();
+        assert!(!chain.has_seen(&dropped.get_hash()));
+        let blocks = chain.get_blocks();
+        drop(chain);
+
+        let mut replayed = Chain::open_with_wal(&path).unwrap();
+        assert_eq!(blocks, replayed.get_blocks());
+        assert_eq!(Ok(true), replayed.submit_block(dropped));
+        Ok(())
+    }
+
+    #[test]
+    fn test_drain_invalid_suffix_keeps_time_locked_blocks() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        let release = SystemTime::now() + Duration::from_secs(3600);
+        let locked =
+            Block::mine(&chain.blocks[1], "sealed bid".to_string()).with_not_before(release);
+        chain.push_indexed(locked);
+        assert_eq!(Err(ValidationError::TimeLocked), chain.validate());
+
+        assert_eq!(Ok(vec![]), chain.drain_invalid_suffix());
+        assert_eq!(3, chain.len());
+
+        chain.blocks[1].set_payload("tampered payload".to_string());
+        assert_eq!(2, chain.drain_invalid_suffix().unwrap().len());
+        assert_eq!(1, chain.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_drain_invalid_suffix_bad_genesis() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.blocks[0] = Block::network_genesis("private net");
+
+        assert_eq!(
+            Err(ValidationError::BadGenesisBlock),
+            chain.drain_invalid_suffix()
+        );
+        assert_eq!(2, chain.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_audit() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        for i in 1..6 {
+            chain.add_block(format!("block {i}"))?;
+        }
+        assert!(chain.audit().is_clean());
+
+        chain.blocks[2].set_payload("tampered payload".to_string());
+        chain.blocks[4] = Block::new(
+            UNIX_EPOCH + Duration::from_secs(1),
+            Hash::default(),
+            "unlinked block".to_string(),
+        );
+
+        let report = chain.audit();
+        assert!(report.genesis_matches);
+        assert_eq!(vec![4, 5], report.broken_links);
+        assert_eq!(vec![2], report.invalid_hashes);
+        assert_eq!(vec![4], report.timestamp_anomalies);
+        assert!(!report.is_clean());
+
+        chain.blocks[0] = Block::network_genesis("private net");
+        assert!(!chain.audit().genesis_matches);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_all_valid_chain() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+
+        chain.add_block("second block".to_string())?;
+
+        assert!(chain.validate_all().is_empty());
+        Ok(())
+    }
+
     #[test]
     fn test_accept_valid_chain() -> Result<(), MiningError> {
         let mut main_chain = Chain::default();
@@ -205,6 +2533,52 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_try_accept_many_takes_longest_valid() -> Result<(), MiningError> {
+        let mut main_chain = Chain::default();
+        main_chain.add_block("second block".to_string())?;
+
+        let mut short = Chain::default();
+        short.add_block("second block".to_string())?;
+        short.add_block("short candidate".to_string())?;
+
+        let mut valid = Chain::default();
+        for i in 2..5 {
+            valid.add_block(format!("valid candidate {i}"))?;
+        }
+
+        let mut invalid = Chain::default();
+        for i in 2..7 {
+            invalid.add_block(format!("invalid candidate {i}"))?;
+        }
+        invalid.blocks[3].set_payload("tampered payload".to_string());
+
+        assert_eq!(
+            Ok(2),
+            main_chain.try_accept_many(vec![short, invalid, valid, Chain::default()])
+        );
+        assert_eq!(4, main_chain.len());
+        assert_eq!("valid candidate 4", main_chain.blocks[3].get_payload());
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_accept_many_all_rejected() -> Result<(), MiningError> {
+        let mut main_chain = Chain::default();
+
+        let mut invalid = Chain::default();
+        invalid.add_block("second block".to_string())?;
+        invalid.blocks[1].set_payload("tampered payload".to_string());
+
+        assert_eq!(
+            Err(ValidationError::InvalidHash),
+            main_chain.try_accept_many(vec![invalid])
+        );
+        assert_eq!(Ok(0), main_chain.try_accept_many(vec![Chain::default()]));
+        assert_eq!(1, main_chain.len());
+        Ok(())
+    }
+
     #[test]
     fn test_refuse_bad_hash_chain() -> Result<(), MiningError> {
         let mut main_chain = Chain::default();
@@ -242,4 +2616,168 @@ mod tests {
         assert_eq!(3, main_chain.len());
         Ok(())
     }
+
+    #[test]
+    fn test_fork_point() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        for i in 1..6 {
+            chain.add_block(format!("block {i}"))?;
+        }
+        assert_eq!(Some(5), chain.fork_point(&chain.archive_headers()));
+        assert_eq!(Some(2), chain.fork_point(&chain.archive_headers()[..3]));
+
+        let mut fork = chain.clone_truncated(3).unwrap();
+        for i in 4..8 {
+            fork.add_block(format!("forked block {i}"))?;
+        }
+        assert_eq!(Some(3), chain.fork_point(&fork.archive_headers()));
+        assert_eq!(Some(3), fork.fork_point(&chain.archive_headers()));
+
+        let mut other = Chain::new_network("private net");
+        other.add_block("block 1".to_string())?;
+        assert_eq!(None, chain.fork_point(&other.archive_headers()));
+        assert_eq!(None, chain.fork_point(&[]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_agrees_with() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        let mut longer = chain.clone_truncated(2).unwrap();
+        longer.add_block("fourth block".to_string())?;
+
+        assert!(chain.agrees_with(&longer, 2));
+        assert!(longer.agrees_with(&chain, 2));
+        assert!(!chain.agrees_with(&longer, 3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_agrees_with_divergence() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+
+        let mut other = Chain::default();
+        other.add_block("other second block".to_string())?;
+
+        assert!(chain.agrees_with(&other, 0));
+        assert!(!chain.agrees_with(&other, 1));
+        assert!(!chain.agrees_with(&other, 10));
+        Ok(())
+    }
+
+    #[test]
+    fn test_accept_shallow_reorg() -> Result<(), MiningError> {
+        let mut main_chain = Chain::with_config(ChainConfig {
+            max_reorg_depth: 1,
+            ..ChainConfig::default()
+        });
+        main_chain.add_block("second block".to_string())?;
+        main_chain.add_block("third block".to_string())?;
+        main_chain.add_block("fourth block".to_string())?;
+
+        let mut incoming_chain = Chain {
+            blocks: main_chain.blocks[..3].to_vec(),
+            ..Chain::default()
+        };
+        incoming_chain.add_block("forked fourth block".to_string())?;
+        incoming_chain.add_block("forked fifth block".to_string())?;
+
+        assert_eq!(Ok(()), main_chain.accept(incoming_chain));
+        assert_eq!(5, main_chain.len());
+        assert_eq!("forked fourth block", main_chain.blocks[3].get_payload());
+        assert_eq!(Ok(()), main_chain.validate());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reorg_counters() -> Result<(), MiningError> {
+        let mut main_chain = Chain::default();
+        main_chain.add_block("second block".to_string())?;
+        main_chain.add_block("third block".to_string())?;
+        main_chain.add_block("fourth block".to_string())?;
+
+        // a pure extension is no reorg
+        let mut extended = main_chain.clone_truncated(3).unwrap();
+        extended.add_block("fifth block".to_string())?;
+        main_chain.accept(extended).unwrap();
+        assert_eq!(0, main_chain.reorg_count());
+
+        let mut shallow = main_chain.clone_truncated(3).unwrap();
+        shallow.add_block("forked fifth block".to_string())?;
+        shallow.add_block("forked sixth block".to_string())?;
+        main_chain.accept(shallow).unwrap();
+        assert_eq!(1, main_chain.reorg_count());
+        assert_eq!(1, main_chain.max_reorg_depth_seen());
+
+        let mut deeper = main_chain.clone_truncated(2).unwrap();
+        for i in 4..8 {
+            deeper.add_block(format!("forked block {i}"))?;
+        }
+        main_chain.accept(deeper).unwrap();
+        assert_eq!(2, main_chain.reorg_count());
+        assert_eq!(3, main_chain.max_reorg_depth_seen());
+        Ok(())
+    }
+
+    #[test]
+    fn test_accept_with_foreign_genesis() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.blocks[0] = Block::network_genesis("private net");
+        let mut other = Chain::default();
+        other.add_block("second block".to_string())?;
+
+        assert_eq!(Err(ValidationError::BadGenesisBlock), chain.accept(other));
+        assert_eq!(1, chain.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_refuse_deep_reorg() -> Result<(), MiningError> {
+        let mut main_chain = Chain::with_config(ChainConfig {
+            max_reorg_depth: 1,
+            ..ChainConfig::default()
+        });
+        main_chain.add_block("second block".to_string())?;
+        main_chain.add_block("third block".to_string())?;
+        main_chain.add_block("fourth block".to_string())?;
+
+        let mut incoming_chain = Chain::default();
+        incoming_chain.add_block("forked second block".to_string())?;
+        incoming_chain.add_block("forked third block".to_string())?;
+        incoming_chain.add_block("forked fourth block".to_string())?;
+        incoming_chain.add_block("forked fifth block".to_string())?;
+
+        assert_eq!(
+            Err(ValidationError::ReorgTooDeep { depth: 3 }),
+            main_chain.accept(incoming_chain)
+        );
+        assert_eq!(4, main_chain.len());
+        assert_eq!("fourth block", main_chain.blocks[3].get_payload());
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_seed_networks_share_genesis() {
+        let chain = Chain::new_network("private net");
+        let other = Chain::new_network("private net");
+
+        assert_eq!(chain.blocks[0].get_hash(), other.blocks[0].get_hash());
+        assert_eq!(Ok(()), chain.validate());
+    }
+
+    #[test]
+    fn test_different_seed_networks_differ() -> Result<(), MiningError> {
+        let mut chain = Chain::new_network("private net");
+        let mut other = Chain::new_network("other net");
+
+        assert_ne!(chain.blocks[0].get_hash(), other.blocks[0].get_hash());
+
+        other.add_block("second block".to_string())?;
+        assert_eq!(Err(ValidationError::BadGenesisBlock), chain.accept(other));
+        Ok(())
+    }
 }
@@ -0,0 +1,40 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{block::BlockHeader, hash::Hash};
+
+/// Compact claim about a chain: which network it is on, where its tip is and how tall it is.
+#[cfg(feature = "serde")]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub struct ChainCommitment {
+    pub genesis_hash: Hash,
+    pub tip_hash: Hash,
+    pub height: u64,
+}
+
+#[cfg(not(feature = "serde"))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ChainCommitment {
+    pub genesis_hash: Hash,
+    pub tip_hash: Hash,
+    pub height: u64,
+}
+
+impl ChainCommitment {
+    /// Checks the commitment against a header chain: it has to start at `expected_genesis`, be
+    /// linked all the way through and end at the committed tip and height.
+    pub fn verify(&self, headers: &[BlockHeader], expected_genesis: Hash) -> bool {
+        let (genesis, tip) = match (headers.first(), headers.last()) {
+            (Some(genesis), Some(tip)) => (genesis, tip),
+            _ => return false,
+        };
+
+        self.genesis_hash == expected_genesis
+            && genesis.get_hash() == expected_genesis
+            && tip.get_hash() == self.tip_hash
+            && headers.len() as u64 - 1 == self.height
+            && headers
+                .windows(2)
+                .all(|headers| headers[0].get_hash() == headers[1].get_prev_hash())
+    }
+}
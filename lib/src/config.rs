@@ -0,0 +1,97 @@
+/// Default for [`ValidationPolicy::max_future_drift`].
+pub const DEFAULT_MAX_FUTURE_DRIFT: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// Cap on [`ValidationPolicy::max_future_drift`] for blocks under rule version 2 and later.
+pub const MAX_FUTURE_DRIFT_V2: Duration = Duration::from_secs(15 * 60);
+
+/// Default for [`ChainConfig::max_reorg_depth`].
+pub const DEFAULT_MAX_REORG_DEPTH: u64 = 6;
+
+use std::time::Duration;
+
+use crate::{
+    block::{Block, GENESIS_RULE_VERSION},
+    hash::Hash,
+};
+
+/// Default for [`ChainConfig::seen_capacity`].
+pub const DEFAULT_SEEN_CAPACITY: usize = 1024;
+
+/// Known-good `(height, hex hash)` pairs pinned into release builds, the default for
+/// [`ChainConfig::checkpoints`].
+pub const CHECKPOINTS: &[(u64, &str)] = &[];
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ChainConfig {
+    /// The block every valid chain on this network has to start with.
+    pub genesis: Block,
+    /// How many blocks below the current tip an incoming chain is allowed to replace when
+    /// accepted. Pure extensions of the current chain (depth 0) are always allowed.
+    pub max_reorg_depth: u64,
+    /// How many recently processed block hashes are remembered to drop duplicates arriving from
+    /// several peers.
+    pub seen_capacity: usize,
+    /// Check every block against the tip as it is appended, instead of trusting locally mined
+    /// or already validated blocks. Off by default as it adds a hash per append.
+    pub strict: bool,
+    /// Blocks every chain has to hold at the given heights. Chains that disagree are rejected
+    /// by [`Chain::accept`](crate::Chain::accept) and imports.
+    pub checkpoints: Vec<(u64, Hash)>,
+    /// Consensus rule version new blocks are mined under, see [`Block::with_version`].
+    pub rule_version: u16,
+    /// Fewest blocks, genesis included, [`Chain::replace_range`](crate::Chain::replace_range)
+    /// may leave in the chain.
+    pub min_len: usize,
+}
+
+impl Default for ChainConfig {
+    fn default() -> Self {
+        Self {
+            genesis: Block::genesis(),
+            max_reorg_depth: DEFAULT_MAX_REORG_DEPTH,
+            seen_capacity: DEFAULT_SEEN_CAPACITY,
+            strict: false,
+            checkpoints: CHECKPOINTS
+                .iter()
+                .map(|&(height, hash)| {
+                    (height, Hash::from_hex(hash).expect("valid checkpoint hash"))
+                })
+                .collect(),
+            rule_version: GENESIS_RULE_VERSION,
+            min_len: 1,
+        }
+    }
+}
+
+/// Toggles for the checks [`Chain::validate_with`](crate::Chain::validate_with) runs on top of
+/// the hash linkage, which is always checked. The default is the strict policy used by
+/// [`Chain::validate`](crate::Chain::validate).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ValidationPolicy {
+    /// Reject blocks timestamped before their predecessor.
+    pub monotonic_timestamps: bool,
+    /// Reject blocks timestamped further than this in the future, if set.
+    pub max_future_drift: Option<Duration>,
+}
+
+impl ValidationPolicy {
+    /// Future drift tolerated for a block under rule `version`: version 2 tightened it to at
+    /// most [`MAX_FUTURE_DRIFT_V2`].
+    pub fn max_future_drift_for(&self, version: u16) -> Option<Duration> {
+        match version {
+            0..=1 => self.max_future_drift,
+            _ => self
+                .max_future_drift
+                .map(|drift| drift.min(MAX_FUTURE_DRIFT_V2)),
+        }
+    }
+}
+
+impl Default for ValidationPolicy {
+    fn default() -> Self {
+        Self {
+            monotonic_timestamps: true,
+            max_future_drift: Some(DEFAULT_MAX_FUTURE_DRIFT),
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::{
+    fs, io,
+    path::Path,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    block::{Block, BlockError, Payload},
+    chain::Chain,
+    config::ChainConfig,
+    hash::Hash,
+};
+
+/// Genesis of a private network as distributed to its nodes, e.g. as a JSON file read by
+/// [`Chain::new_from_genesis_file`]. Nodes loading the same spec build the same genesis block
+/// and can sync with each other.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisSpec {
+    /// Id the network goes by, e.g. in a [`ChainRegistry`](crate::ChainRegistry).
+    pub network_id: String,
+    pub payload: Payload,
+    /// Milliseconds since the Unix epoch.
+    #[serde(default)]
+    pub timestamp: u64,
+    /// Defaults to [`Hash::zero`], "no previous block".
+    #[serde(default)]
+    pub prev_hash: Hash,
+}
+
+impl GenesisSpec {
+    pub fn genesis(&self) -> Result<Block, BlockError> {
+        Block::try_new(
+            UNIX_EPOCH + Duration::from_millis(self.timestamp),
+            self.prev_hash,
+            self.payload.clone(),
+        )
+    }
+}
+
+/// Errors bootstrapping a chain with [`Chain::new_from_genesis_file`].
+#[derive(Debug)]
+pub enum GenesisSpecError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    InvalidBlock(BlockError),
+}
+
+impl Chain {
+    /// Starts a chain on the network described by `spec`.
+    pub fn from_genesis_spec(spec: &GenesisSpec) -> Result<Self, BlockError> {
+        Ok(Self::with_config(ChainConfig {
+            genesis: spec.genesis()?,
+            // the pinned checkpoints belong to the default network
+            checkpoints: vec![],
+            ..ChainConfig::default()
+        }))
+    }
+
+    /// Starts a chain on the network described by the JSON [`GenesisSpec`] at `path`.
+    pub fn new_from_genesis_file<P: AsRef<Path>>(path: P) -> Result<Self, GenesisSpecError> {
+        let spec = fs::read_to_string(path).map_err(GenesisSpecError::Io)?;
+        let spec: GenesisSpec = serde_json::from_str(&spec).map_err(GenesisSpecError::Parse)?;
+        Self::from_genesis_spec(&spec).map_err(GenesisSpecError::InvalidBlock)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{GenesisSpec, GenesisSpecError};
+    use crate::{canonical_genesis_hash, hash::Hash, Chain, MiningError};
+
+    const SPEC: &str = r#"{
+        "network_id": "alpha",
+        "payload": "Alpha genesis",
+        "timestamp": 1700000000000
+    }"#;
+
+    #[test]
+    fn test_genesis_file_is_deterministic() -> Result<(), MiningError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("genesis.json");
+        fs::write(&path, SPEC).unwrap();
+
+        let mut first = Chain::new_from_genesis_file(&path).unwrap();
+        let mut second = Chain::new_from_genesis_file(&path).unwrap();
+        assert_eq!(first.genesis_hash(), second.genesis_hash());
+        assert_ne!(Some(canonical_genesis_hash()), first.genesis_hash());
+        assert_eq!(Some(&"Alpha genesis".to_string()), first.genesis_payload());
+
+        first.add_block("second block".to_string())?;
+        assert_eq!(Ok(()), second.accept(first));
+        assert_eq!(2, second.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_genesis_spec_defaults() {
+        let spec: GenesisSpec =
+            serde_json::from_str(r#"{ "network_id": "beta", "payload": "Beta genesis" }"#).unwrap();
+
+        assert_eq!(0, spec.timestamp);
+        assert_eq!(Hash::zero(), spec.prev_hash);
+        assert!(Chain::from_genesis_spec(&spec).unwrap().is_valid());
+    }
+
+    #[test]
+    fn test_genesis_file_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("genesis.json");
+        assert!(matches!(
+            Chain::new_from_genesis_file(&path),
+            Err(GenesisSpecError::Io(_))
+        ));
+
+        fs::write(&path, r#"{ "network_id": "alpha" }"#).unwrap();
+        assert!(matches!(
+            Chain::new_from_genesis_file(&path),
+            Err(GenesisSpecError::Parse(_))
+        ));
+    }
+}
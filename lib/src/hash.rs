@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{cmp::Ordering, fmt::Display};
 
 #[cfg(feature = "serde")]
 use serde::{
@@ -7,13 +7,48 @@ use serde::{
 };
 
 #[cfg(feature = "serde")]
-#[derive(Default, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Default, Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Hash(u128, u128);
 
 #[cfg(not(feature = "serde"))]
-#[derive(Default, Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Default, Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Hash(u128, u128);
 
+/// How a 512-bit digest is collapsed into the 256 bits a [`Hash`] holds.
+///
+/// `Truncate` keeps the first half of the digest, so every bit of the result is a plain digest
+/// bit, the same tradeoff SHA-512/256 makes. `XorHalves` folds the second half onto the first so
+/// no output bit is discarded, but the result no longer matches what other SHA-512 tools print
+/// and it does not make collisions any harder to find than truncation: both top out at the
+/// 128-bit collision resistance of a 256-bit output.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Fold {
+    Truncate,
+    XorHalves,
+}
+
+impl Fold {
+    fn apply(&self, digest: &[u8]) -> [u8; 32] {
+        let (lower, upper) = digest.split_at(32);
+        match self {
+            Self::Truncate => to_byte_array(lower.to_vec()),
+            Self::XorHalves => to_byte_array(
+                lower
+                    .iter()
+                    .zip(upper.iter())
+                    .map(|(lower, upper)| lower ^ upper)
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// A difficulty target packed into 32 bits like Bitcoin's `nBits`: the top byte is the target's
+/// length in bytes and the lower 23 bits its leading digits, bit 23 being a sign bit that a
+/// target never sets. See [`Hash::from_compact`] and [`Hash::to_compact`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CompactTarget(pub u32);
+
 pub trait Hashable {
     fn bytes(&self) -> Vec<u8>;
 
@@ -23,6 +58,16 @@ pub trait Hashable {
 }
 
 impl Hash {
+    /// The all-zero hash, which no block hashes to in practice. It is the `prev_hash` of the
+    /// genesis block, standing for "no previous block".
+    pub const fn zero() -> Self {
+        Self(0, 0)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+
     pub fn from_bytes(bytes: &[u8]) -> Self {
         let hash = crypto_hash::digest(crypto_hash::Algorithm::SHA256, bytes);
 
@@ -32,9 +77,111 @@ impl Hash {
         )
     }
 
+    /// Hashes `bytes` with SHA512, collapsing the digest to 256 bits as `fold` says.
+    pub fn from_bytes_sha512(bytes: &[u8], fold: Fold) -> Self {
+        let hash = crypto_hash::digest(crypto_hash::Algorithm::SHA512, bytes);
+
+        Self::from_digest(fold.apply(&hash))
+    }
+
+    /// Builds a hash straight from the 32 bytes of a digest, without hashing them again.
+    pub(crate) fn from_digest(digest: [u8; 32]) -> Self {
+        Self(
+            u128::from_le_bytes(to_byte_array(digest[0..16].to_vec())),
+            u128::from_le_bytes(to_byte_array(digest[16..32].to_vec())),
+        )
+    }
+
+    /// The digest bytes in their standard big-endian order, as printed by `sha256sum` and other
+    /// SHA256 tools. The little-endian conversions used inside only concern how the two words
+    /// are stored: [`Hash::bytes`] and [`Hash::to_hex`] already give the digest in this order.
+    pub fn to_bytes_be(&self) -> [u8; 32] {
+        to_byte_array(self.bytes())
+    }
+
+    /// Inverse of [`Hash::to_bytes_be`], reading a digest produced by an external tool.
+    pub fn from_bytes_be(digest: [u8; 32]) -> Self {
+        Self::from_digest(digest)
+    }
+
     pub fn bytes(&self) -> Vec<u8> {
         [self.0.to_le_bytes(), self.1.to_le_bytes()].concat()
     }
+
+    /// The 64 hex characters of the hash, as printed by `Display` and read by [`Hash::from_hex`].
+    pub fn to_hex(&self) -> String {
+        self.encode_hex(&mut [0; 64]).to_string()
+    }
+
+    // hex digits of both words written into `buffer`, so `Display` and serde don't allocate
+    fn encode_hex<'a>(&self, buffer: &'a mut [u8; 64]) -> &'a str {
+        let (low, high) = buffer.split_at_mut(32);
+        hex::encode_to_slice(self.0.to_le_bytes(), low).expect("32 hex digits per word");
+        hex::encode_to_slice(self.1.to_le_bytes(), high).expect("32 hex digits per word");
+        std::str::from_utf8(buffer).expect("hex digits are ascii")
+    }
+
+    /// First 8 hex characters followed by an ellipsis, for logs where the full hash is noise.
+    pub fn short(&self) -> String {
+        format!("{}…", &self.to_hex()[..8])
+    }
+
+    /// The hex form split into its two 128-bit words, for when grouping helps readability.
+    pub fn to_spaced_hex(&self) -> String {
+        format!(
+            "{} {}",
+            hex::encode(self.0.to_le_bytes()),
+            hex::encode(self.1.to_le_bytes())
+        )
+    }
+
+    /// Unpacks a compact target, `None` if it is negative or doesn't fit in 256 bits.
+    pub fn from_compact(compact: CompactTarget) -> Option<Self> {
+        let size = (compact.0 >> 24) as usize;
+        let mantissa = compact.0 & 0x007f_ffff;
+        if compact.0 & 0x0080_0000 != 0 && mantissa != 0 {
+            return None;
+        }
+
+        let mut digest = [0; 32];
+        if size <= 3 {
+            let mantissa = mantissa >> (8 * (3 - size));
+            digest[29..].copy_from_slice(&mantissa.to_be_bytes()[1..]);
+        } else {
+            // digits falling before the first byte have to be zero for the target to fit
+            for (i, byte) in mantissa.to_be_bytes()[1..].iter().enumerate() {
+                match (32 + i).checked_sub(size) {
+                    Some(position) => digest[position] = *byte,
+                    None if *byte == 0 => {}
+                    None => return None,
+                }
+            }
+        }
+        Some(Self::from_digest(digest))
+    }
+
+    /// Packs the hash as a compact target, keeping its three leading bytes (two when the first
+    /// has its top bit set): the target it unpacks to is rounded down, never above this one.
+    pub fn to_compact(&self) -> CompactTarget {
+        let digest = self.to_bytes_be();
+        let mut size = digest.iter().skip_while(|byte| **byte == 0).count();
+        let start = 32 - size;
+        let mut mantissa = (0..3).fold(0u32, |mantissa, i| {
+            mantissa << 8 | u32::from(*digest.get(start + i).unwrap_or(&0))
+        });
+        // a set bit 23 would read as a sign, move the digits down a byte instead
+        if mantissa & 0x0080_0000 != 0 {
+            mantissa >>= 8;
+            size += 1;
+        }
+        CompactTarget((size as u32) << 24 | mantissa)
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, hex::FromHexError> {
+        let mut digest = [0; 32];
+        hex::decode_to_slice(hex, &mut digest)?;
+        Ok(Self::from_digest(digest))
+    }
 }
 
 fn to_byte_array<T, const N: usize>(v: Vec<T>) -> [T; N] {
@@ -42,15 +189,23 @@ fn to_byte_array<T, const N: usize>(v: Vec<T>) -> [T; N] {
         .unwrap_or_else(|v: Vec<T>| panic!("Expected a Vec of length {} but it was {}", N, v.len()))
 }
 
+/// Hashes order as the 256-bit big-endian numbers their [`Hash::bytes`] spell, so a hash with
+/// more leading zero bits is smaller.
+impl Ord for Hash {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bytes().cmp(&other.bytes())
+    }
+}
+
+impl PartialOrd for Hash {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Display for Hash {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{} {}",
-            hex::encode(self.0.to_le_bytes()),
-            hex::encode(self.1.to_le_bytes())
-        )?;
-        Ok(())
+        f.write_str(self.encode_hex(&mut [0; 64]))
     }
 }
 
@@ -60,9 +215,7 @@ impl Serialize for Hash {
     where
         S: serde::Serializer,
     {
-        let mut bytes: Vec<u8> = self.0.to_le_bytes().to_vec();
-        bytes.extend(self.1.to_le_bytes());
-        serializer.serialize_str(&hex::encode(bytes))
+        serializer.serialize_str(self.encode_hex(&mut [0; 64]))
     }
 }
 
@@ -76,15 +229,11 @@ impl<'de> Visitor<'de> for HashVisitor {
         formatter.write_str("a valid hexadecimal hash")
     }
 
-    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        let mut lower = hex::decode(&v[0..16]).unwrap();
-        let upper = hex::decode(&v[16..32]).unwrap();
-        lower.extend_from_slice(&upper);
-
-        Ok(Hash::from_bytes(&lower))
+        Hash::from_hex(v).map_err(E::custom)
     }
 }
 
@@ -97,3 +246,142 @@ impl<'de> Deserialize<'de> for Hash {
         deserializer.deserialize_str(HashVisitor)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CompactTarget, Fold, Hash};
+
+    #[test]
+    fn test_ord_follows_bytes() {
+        let low = Hash::from_hex(&format!("00ff{}", "0".repeat(60))).unwrap();
+        let high = Hash::from_hex(&format!("0100{}", "0".repeat(60))).unwrap();
+
+        assert!(Hash::zero() < low);
+        assert!(low < high);
+        assert_eq!(Some(high), [low, high, Hash::zero()].into_iter().max());
+    }
+
+    #[test]
+    fn test_hex_encoding() {
+        let hash = Hash::from_bytes("Hello world!".as_bytes());
+        let expected = hex::encode(hash.bytes());
+
+        assert_eq!(expected, hash.to_hex());
+        assert_eq!(expected, hash.to_string());
+        assert_eq!(hash.to_spaced_hex().replace(' ', ""), hash.to_hex());
+        assert_eq!(Ok(hash), Hash::from_hex(&hash.to_string()));
+    }
+
+    #[test]
+    fn test_short() {
+        let hash = Hash::from_bytes("Hello world!".as_bytes());
+        let short = hash.short();
+
+        assert_eq!(9, short.chars().count());
+        assert!(short.ends_with('…'));
+        assert!(hash.to_hex().starts_with(short.trim_end_matches('…')));
+    }
+
+    #[test]
+    fn test_to_bytes_be_matches_sha256_vector() {
+        let hash = Hash::from_bytes(b"abc");
+        let expected = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        assert_eq!(expected, hex::encode(hash.to_bytes_be()));
+        assert_eq!(expected, hash.to_hex());
+        assert_eq!(hash, Hash::from_bytes_be(hash.to_bytes_be()));
+    }
+
+    #[test]
+    fn test_compact_round_trip() {
+        for compact in [
+            0x1d00ffff,
+            0x1b0404cb,
+            0x0312_3456,
+            0x0112_0000,
+            0x2000_ffff,
+            0,
+        ] {
+            let target = Hash::from_compact(CompactTarget(compact)).unwrap();
+            assert_eq!(CompactTarget(compact), target.to_compact());
+        }
+
+        let target = Hash::from_compact(CompactTarget(0x1d00ffff)).unwrap();
+        assert_eq!(
+            Ok(target),
+            Hash::from_hex(&format!("00000000ffff{}", "0".repeat(52)))
+        );
+        // only the leading bytes are kept, rounding down
+        let target = Hash::from_hex(&format!("00001bcdef12{}", "f".repeat(52))).unwrap();
+        let rounded = Hash::from_compact(target.to_compact()).unwrap();
+        assert_eq!(
+            Ok(rounded),
+            Hash::from_hex(&format!("00001bcdef{}", "0".repeat(54)))
+        );
+    }
+
+    #[test]
+    fn test_compact_preserves_order() {
+        let targets: Vec<Hash> = [1, 8, 9, 20, 33, 200]
+            .iter()
+            .rev()
+            .map(|bits| crate::Block::difficulty_target(*bits))
+            .collect();
+        let compacts: Vec<Hash> = targets
+            .iter()
+            .map(|target| Hash::from_compact(target.to_compact()).unwrap())
+            .collect();
+
+        assert!(compacts.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(targets
+            .iter()
+            .zip(&compacts)
+            .all(|(target, compact)| compact <= target));
+    }
+
+    #[test]
+    fn test_compact_rejects_negative_and_overflow() {
+        assert_eq!(None, Hash::from_compact(CompactTarget(0x0480_0001)));
+        assert_eq!(None, Hash::from_compact(CompactTarget(0x2201_0000)));
+        assert!(Hash::from_compact(CompactTarget(0x2100_00ff)).is_some());
+    }
+
+    #[test]
+    fn test_is_zero() {
+        assert!(Hash::zero().is_zero());
+        assert!(Hash::from_hex(&"0".repeat(64)).unwrap().is_zero());
+        assert!(!Hash::from_bytes(&[]).is_zero());
+        assert!(!Hash::from_bytes(&[1]).is_zero());
+    }
+
+    #[test]
+    fn test_display_round_trips_through_hex() {
+        let hash = Hash::from_bytes("Hello world!".as_bytes());
+
+        assert_eq!(64, format!("{hash}").len());
+        assert_eq!(Ok(hash), Hash::from_hex(&format!("{hash}")));
+        assert_eq!(hash.to_hex(), hash.to_spaced_hex().replace(' ', ""));
+    }
+
+    #[test]
+    fn test_from_hex_rejects_bad_input() {
+        assert!(Hash::from_hex("abcd").is_err());
+        assert!(Hash::from_hex(&"zz".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn test_sha512_folds_differ() {
+        let truncated = Hash::from_bytes_sha512("Hello world!".as_bytes(), Fold::Truncate);
+        let xored = Hash::from_bytes_sha512("Hello world!".as_bytes(), Fold::XorHalves);
+
+        assert_ne!(truncated, xored);
+    }
+
+    #[test]
+    fn test_sha512_truncate_keeps_first_half() {
+        let digest = crypto_hash::digest(crypto_hash::Algorithm::SHA512, "Hello world!".as_bytes());
+        let truncated = Hash::from_bytes_sha512("Hello world!".as_bytes(), Fold::Truncate);
+
+        assert_eq!(digest[..32].to_vec(), truncated.bytes());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{block::BlockHeader, chain::ValidationError, hash::Hash};
+
+/// Archived chain holding only block headers, as written by
+/// [`Chain::archive_headers`](crate::Chain::archive_headers). The payloads are gone, but each
+/// header keeps the hash of its payload, so a body fetched again later can still be checked.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HeaderChain {
+    headers: Vec<BlockHeader>,
+}
+
+impl HeaderChain {
+    /// Loads archived headers, genesis first, making sure they start at `genesis_hash` and link
+    /// up all the way through.
+    pub fn load(headers: Vec<BlockHeader>, genesis_hash: Hash) -> Result<Self, ValidationError> {
+        let chain = Self { headers };
+        chain.validate_headers(genesis_hash)?;
+        Ok(chain)
+    }
+
+    /// Checks the linkage of the headers. Without the payloads the block hashes themselves
+    /// can't be recomputed and are taken as stored.
+    pub fn validate_headers(&self, genesis_hash: Hash) -> Result<(), ValidationError> {
+        let genesis = self.headers.first().ok_or(ValidationError::EmptyChain)?;
+        if genesis.get_hash() != genesis_hash {
+            return Err(ValidationError::BadGenesisBlock);
+        }
+
+        self.headers.windows(2).try_for_each(|headers| {
+            if headers[0].get_hash() != headers[1].get_prev_hash() {
+                return Err(ValidationError::InvalidPrevHash);
+            }
+            if headers[1].get_timestamp() < headers[0].get_timestamp() {
+                return Err(ValidationError::NonMonotonicTimestamp);
+            }
+            Ok(())
+        })
+    }
+
+    pub fn get_header(&self, height: u64) -> Option<&BlockHeader> {
+        self.headers.get(usize::try_from(height).ok()?)
+    }
+
+    /// Whether `payload` is the body of the block at `height`, e.g. one fetched again from a peer.
+    pub fn verify_body(&self, height: u64, payload: &str) -> bool {
+        let payload_hash = Hash::from_bytes(payload.as_bytes());
+        self.get_header(height)
+            .is_some_and(|header| header.get_payload_hash() == payload_hash)
+    }
+
+    pub fn headers(&self) -> &[BlockHeader] {
+        &self.headers
+    }
+
+    pub fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HeaderChain;
+    use crate::{chain::ValidationError, hash::Hash, Chain, MiningError};
+
+    #[test]
+    fn test_load_archived_headers() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+        let genesis_hash = chain.genesis_hash().unwrap();
+
+        let archive = HeaderChain::load(chain.archive_headers(), genesis_hash).unwrap();
+        assert_eq!(chain.len(), archive.len());
+        assert_eq!(Some(&chain.tip().unwrap().header()), archive.get_header(2));
+        assert!(archive.verify_body(1, "second block"));
+        assert!(!archive.verify_body(1, "swapped block"));
+        assert!(!archive.verify_body(3, "second block"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_rejects_broken_headers() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+        let genesis_hash = chain.genesis_hash().unwrap();
+
+        let mut headers = chain.archive_headers();
+        headers.remove(1);
+        assert_eq!(
+            Err(ValidationError::InvalidPrevHash),
+            HeaderChain::load(headers, genesis_hash)
+        );
+        assert_eq!(
+            Err(ValidationError::BadGenesisBlock),
+            HeaderChain::load(chain.archive_headers(), Hash::default())
+        );
+        assert_eq!(
+            Err(ValidationError::EmptyChain),
+            HeaderChain::load(vec![], genesis_hash)
+        );
+        Ok(())
+    }
+}
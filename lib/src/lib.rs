@@ -1,6 +1,36 @@
+mod audit;
 mod block;
 mod chain;
+mod commitment;
+mod config;
+#[cfg(feature = "serde")]
+mod genesis;
 mod hash;
+mod header_chain;
+mod mmr;
+mod registry;
+mod seen;
+mod sync;
+mod wal;
 
-pub use block::Block;
-pub use chain::{Chain, MiningError};
+pub use audit::AuditReport;
+pub use block::{
+    Block, BlockError, BlockHeader, BLOCK_HEADER_OVERHEAD, GENESIS_RULE_VERSION,
+    LATEST_RULE_VERSION, MAX_PAYLOAD_BYTES, PAYLOAD_LENGTH_RULE_VERSION, REDACTED_PAYLOAD,
+};
+#[cfg(feature = "serde")]
+pub use chain::JsonError;
+pub use chain::{canonical_genesis_hash, Chain, MiningError, ValidationError};
+pub use commitment::ChainCommitment;
+pub use config::{
+    ChainConfig, ValidationPolicy, CHECKPOINTS, DEFAULT_MAX_FUTURE_DRIFT, DEFAULT_MAX_REORG_DEPTH,
+    DEFAULT_SEEN_CAPACITY, MAX_FUTURE_DRIFT_V2,
+};
+#[cfg(feature = "serde")]
+pub use genesis::{GenesisSpec, GenesisSpecError};
+pub use hash::{CompactTarget, Fold, Hash};
+pub use header_chain::HeaderChain;
+pub use mmr::MmrProof;
+pub use registry::ChainRegistry;
+pub use sync::{SyncAction, SyncSession, SyncState};
+pub use wal::WalError;
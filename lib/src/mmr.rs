@@ -0,0 +1,120 @@
+use crate::hash::Hash;
+
+const NODE_PREFIX: u8 = 1;
+const BAG_PREFIX: u8 = 2;
+
+/// Merkle Mountain Range over block hashes: a list of perfect Merkle trees ("peaks") of
+/// decreasing size, merged as leaves are pushed and bagged together into a single root.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Mmr {
+    leaves: Vec<Hash>,
+    peaks: Vec<(u32, Hash)>,
+}
+
+impl Mmr {
+    pub(crate) fn push(&mut self, leaf: Hash) {
+        self.leaves.push(leaf);
+        self.peaks.push((0, leaf));
+
+        while let [.., (left_height, left), (right_height, right)] = self.peaks[..] {
+            if left_height != right_height {
+                break;
+            }
+            self.peaks.truncate(self.peaks.len() - 2);
+            self.peaks.push((left_height + 1, hash_node(&left, &right)));
+        }
+    }
+
+    pub(crate) fn root(&self) -> Option<Hash> {
+        bag(&self.peak_hashes())
+    }
+
+    pub(crate) fn proof(&self, leaf_index: u64) -> Option<MmrProof> {
+        let leaf_index = usize::try_from(leaf_index).ok()?;
+        if leaf_index >= self.leaves.len() {
+            return None;
+        }
+
+        // find the peak the leaf ended up under, peaks cover contiguous ranges of leaves
+        let mut start = 0;
+        let mut peak = None;
+        for (peak_index, (height, _)) in self.peaks.iter().enumerate() {
+            let size = 1 << height;
+            if leaf_index < start + size {
+                peak = Some((peak_index, size));
+                break;
+            }
+            start += size;
+        }
+        let (peak_index, size) = peak?;
+
+        let mut level = self.leaves[start..start + size].to_vec();
+        let mut index = leaf_index - start;
+        let mut path = vec![];
+        while level.len() > 1 {
+            path.push(level[index ^ 1]);
+            level = level
+                .chunks(2)
+                .map(|pair| hash_node(&pair[0], &pair[1]))
+                .collect();
+            index >>= 1;
+        }
+
+        Some(MmrProof {
+            peak_index,
+            local_index: (leaf_index - start) as u64,
+            path,
+            peaks: self.peak_hashes(),
+        })
+    }
+
+    fn peak_hashes(&self) -> Vec<Hash> {
+        self.peaks.iter().map(|(_, hash)| *hash).collect()
+    }
+}
+
+/// Inclusion proof of a single leaf: the path up to its peak, then every peak of the range so
+/// the root can be bagged again. Both grow logarithmically with the number of leaves.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MmrProof {
+    pub peak_index: usize,
+    pub local_index: u64,
+    pub path: Vec<Hash>,
+    pub peaks: Vec<Hash>,
+}
+
+impl MmrProof {
+    pub fn verify(&self, root: Hash, leaf: Hash) -> bool {
+        let mut index = self.local_index;
+        let peak = self.path.iter().fold(leaf, |node, sibling| {
+            let parent = if index & 1 == 0 {
+                hash_node(&node, sibling)
+            } else {
+                hash_node(sibling, &node)
+            };
+            index >>= 1;
+            parent
+        });
+
+        index == 0
+            && self.peaks.get(self.peak_index) == Some(&peak)
+            && bag(&self.peaks) == Some(root)
+    }
+}
+
+fn hash_node(left: &Hash, right: &Hash) -> Hash {
+    let mut bytes = vec![NODE_PREFIX];
+    bytes.extend(left.bytes());
+    bytes.extend(right.bytes());
+    Hash::from_bytes(&bytes)
+}
+
+fn bag(peaks: &[Hash]) -> Option<Hash> {
+    let (last, rest) = peaks.split_last()?;
+    Some(rest.iter().rev().fold(*last, |bagged, peak| {
+        let mut bytes = vec![BAG_PREFIX];
+        bytes.extend(peak.bytes());
+        bytes.extend(bagged.bytes());
+        Hash::from_bytes(&bytes)
+    }))
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use crate::chain::Chain;
+
+/// Independent chains hosted side by side in one process, keyed by network id.
+#[derive(Default)]
+pub struct ChainRegistry {
+    chains: HashMap<String, Chain>,
+}
+
+impl ChainRegistry {
+    /// Hosts `chain` under `network_id`, returning the chain it replaces if any.
+    pub fn insert<S: Into<String>>(&mut self, network_id: S, chain: Chain) -> Option<Chain> {
+        self.chains.insert(network_id.into(), chain)
+    }
+
+    pub fn get(&self, network_id: &str) -> Option<&Chain> {
+        self.chains.get(network_id)
+    }
+
+    pub fn get_mut(&mut self, network_id: &str) -> Option<&mut Chain> {
+        self.chains.get_mut(network_id)
+    }
+
+    pub fn network_ids(&self) -> impl Iterator<Item = &str> {
+        self.chains.keys().map(String::as_str)
+    }
+
+    /// Runs `f` on the chain of `network_id`, `None` if that network isn't hosted.
+    pub fn dispatch<T, F: FnOnce(&mut Chain) -> T>(&mut self, network_id: &str, f: F) -> Option<T> {
+        self.get_mut(network_id).map(f)
+    }
+
+    pub fn len(&self) -> usize {
+        self.chains.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chains.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChainRegistry;
+    use crate::{Chain, MiningError};
+
+    #[test]
+    fn test_independent_networks() -> Result<(), MiningError> {
+        let mut registry = ChainRegistry::default();
+        registry.insert("alpha", Chain::new_network("alpha"));
+        registry.insert("beta", Chain::new_network("beta"));
+
+        registry
+            .dispatch("alpha", |chain| chain.add_block("alpha block".to_string()))
+            .unwrap()?;
+        registry
+            .dispatch("beta", |chain| chain.add_block("beta block".to_string()))
+            .unwrap()?;
+        registry
+            .dispatch("beta", |chain| {
+                chain.add_block("another beta block".to_string())
+            })
+            .unwrap()?;
+
+        let alpha = registry.get("alpha").unwrap();
+        let beta = registry.get("beta").unwrap();
+        assert_eq!(2, alpha.len());
+        assert_eq!(3, beta.len());
+        assert_ne!(alpha.tip_hash(), beta.tip_hash());
+        assert!(alpha.is_valid() && beta.is_valid());
+        Ok(())
+    }
+
+    #[test]
+    fn test_dispatch_unknown_network() {
+        let mut registry = ChainRegistry::default();
+        registry.insert("alpha", Chain::new_network("alpha"));
+
+        assert!(registry.dispatch("gamma", |chain| chain.len()).is_none());
+        assert_eq!(vec!["alpha"], registry.network_ids().collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::hash::Hash;
+
+/// Hashes of recently processed blocks, forgetting the oldest ones past `capacity`.
+#[derive(Default)]
+pub(crate) struct SeenBlocks {
+    capacity: usize,
+    hashes: HashSet<Hash>,
+    order: VecDeque<Hash>,
+}
+
+impl SeenBlocks {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn contains(&self, hash: &Hash) -> bool {
+        self.hashes.contains(hash)
+    }
+
+    pub(crate) fn insert(&mut self, hash: Hash) {
+        if self.hashes.contains(&hash) {
+            self.order.retain(|seen| *seen != hash);
+        } else {
+            self.hashes.insert(hash);
+        }
+        self.order.push_back(hash);
+
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.hashes.remove(&oldest);
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, hash: &Hash) {
+        if self.hashes.remove(hash) {
+            self.order.retain(|seen| seen != hash);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SeenBlocks;
+    use crate::hash::Hash;
+
+    #[test]
+    fn test_evicts_oldest() {
+        let mut seen = SeenBlocks::new(2);
+        let hashes: Vec<Hash> = (0..3u8).map(|i| Hash::from_bytes(&[i])).collect();
+
+        seen.insert(hashes[0]);
+        seen.insert(hashes[1]);
+        seen.insert(hashes[0]);
+        seen.insert(hashes[2]);
+
+        assert!(seen.contains(&hashes[0]));
+        assert!(!seen.contains(&hashes[1]));
+        assert!(seen.contains(&hashes[2]));
+
+        seen.remove(&hashes[2]);
+        seen.insert(hashes[1]);
+        assert!(seen.contains(&hashes[0]));
+        assert!(seen.contains(&hashes[1]));
+        assert!(!seen.contains(&hashes[2]));
+    }
+}
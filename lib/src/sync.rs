@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+
+use crate::{
+    block::{Block, BlockHeader, Payload},
+    chain::{Chain, ValidationError},
+    hash::Hash,
+};
+
+/// Where a [`SyncSession`] stands. Each call to [`SyncSession::step`] moves it by at most one
+/// state, the `Awaiting*` states only being left once the peer's answer is handed over.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SyncState {
+    RequestHeaders,
+    AwaitingHeaders,
+    ValidateHeaders,
+    RequestBodies,
+    AwaitingBodies,
+    Assemble,
+    Commit,
+    Done,
+}
+
+/// What the caller has to do after a [`SyncSession::step`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SyncAction {
+    /// Fetch the peer's whole header chain and pass it to [`SyncSession::receive_headers`].
+    RequestHeaders,
+    /// Fetch the payloads of these blocks and pass them to [`SyncSession::receive_bodies`].
+    RequestBodies(Vec<Hash>),
+    /// Nothing to fetch, step again.
+    Continue,
+    /// The session is over, the chain holds whatever the peer had to offer.
+    Done,
+}
+
+/// Multi-step sync with a single peer: download its headers, check them, download the bodies
+/// missing locally, assemble its chain and hand it to [`Chain::accept`]. The session only keeps
+/// what was downloaded so far, a request that got no answer is repeated by the next step.
+pub struct SyncSession {
+    state: SyncState,
+    genesis_hash: Option<Hash>,
+    headers: Vec<BlockHeader>,
+    shared: usize,
+    bodies: HashMap<Hash, Block>,
+    assembled: Option<Chain>,
+}
+
+impl SyncSession {
+    pub(crate) fn new(genesis_hash: Option<Hash>) -> Self {
+        Self {
+            state: SyncState::RequestHeaders,
+            genesis_hash,
+            headers: vec![],
+            shared: 0,
+            bodies: HashMap::new(),
+            assembled: None,
+        }
+    }
+
+    pub fn state(&self) -> SyncState {
+        self.state
+    }
+
+    /// Advances the session by one state and tells what it needs next.
+    pub fn step(&mut self, chain: &mut Chain) -> Result<SyncAction, ValidationError> {
+        match self.state {
+            SyncState::RequestHeaders | SyncState::AwaitingHeaders => {
+                self.state = SyncState::AwaitingHeaders;
+                Ok(SyncAction::RequestHeaders)
+            }
+            SyncState::ValidateHeaders => {
+                self.validate_headers()?;
+                if self.headers.len() <= chain.len() {
+                    // like `accept`, a chain no longer than ours isn't worth the bodies
+                    self.state = SyncState::Done;
+                    return Ok(SyncAction::Done);
+                }
+                self.shared = chain
+                    .fork_point(&self.headers)
+                    .map_or(0, |height| height as usize + 1);
+                self.state = SyncState::RequestBodies;
+                Ok(SyncAction::Continue)
+            }
+            SyncState::RequestBodies | SyncState::AwaitingBodies => {
+                self.state = SyncState::AwaitingBodies;
+                Ok(SyncAction::RequestBodies(self.missing_bodies()))
+            }
+            SyncState::Assemble => {
+                let mut assembled = (self.shared as u64)
+                    .checked_sub(1)
+                    .and_then(|height| chain.clone_truncated(height))
+                    .ok_or(ValidationError::BadGenesisBlock)?;
+                for header in &self.headers[self.shared..] {
+                    let block = self.bodies[&header.get_hash()].clone();
+                    assembled.push_validated(block)?;
+                }
+                self.assembled = Some(assembled);
+                self.state = SyncState::Commit;
+                Ok(SyncAction::Continue)
+            }
+            SyncState::Commit => {
+                let assembled = self.assembled.take().ok_or(ValidationError::EmptyChain)?;
+                chain.accept(assembled)?;
+                self.state = SyncState::Done;
+                Ok(SyncAction::Done)
+            }
+            SyncState::Done => Ok(SyncAction::Done),
+        }
+    }
+
+    /// Hands over the peer's header chain, genesis first.
+    pub fn receive_headers(&mut self, headers: Vec<BlockHeader>) {
+        if self.state == SyncState::AwaitingHeaders {
+            self.headers = headers;
+            self.state = SyncState::ValidateHeaders;
+        }
+    }
+
+    /// Hands over payloads for some of the requested blocks, in any order. Each is checked
+    /// against its header right away; the session moves on once every body is there.
+    pub fn receive_bodies(&mut self, bodies: Vec<(Hash, Payload)>) -> Result<(), ValidationError> {
+        if self.state != SyncState::AwaitingBodies {
+            return Ok(());
+        }
+
+        for (hash, payload) in bodies {
+            let header = self.headers[self.shared..]
+                .iter()
+                .find(|header| header.get_hash() == hash)
+                .ok_or(ValidationError::InvalidHash)?;
+            let block = Block::from_header_and_payload(*header, payload)?;
+            self.bodies.insert(hash, block);
+        }
+
+        if self.missing_bodies().is_empty() {
+            self.state = SyncState::Assemble;
+        }
+        Ok(())
+    }
+
+    fn validate_headers(&self) -> Result<(), ValidationError> {
+        let genesis = self.headers.first().ok_or(ValidationError::EmptyChain)?;
+        if Some(genesis.get_hash()) != self.genesis_hash {
+            return Err(ValidationError::BadGenesisBlock);
+        }
+        if self
+            .headers
+            .windows(2)
+            .any(|headers| headers[0].get_hash() != headers[1].get_prev_hash())
+        {
+            return Err(ValidationError::InvalidPrevHash);
+        }
+        Ok(())
+    }
+
+    fn missing_bodies(&self) -> Vec<Hash> {
+        self.headers[self.shared..]
+            .iter()
+            .map(BlockHeader::get_hash)
+            .filter(|hash| !self.bodies.contains_key(hash))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SyncAction, SyncState};
+    use crate::{
+        block::{Block, BlockHeader},
+        chain::ValidationError,
+        hash::Hash,
+        Chain, MiningError,
+    };
+
+    fn peer_headers(peer: &Chain) -> Vec<BlockHeader> {
+        peer.iter_headers().collect()
+    }
+
+    fn peer_bodies(peer: &Chain, hashes: &[Hash]) -> Vec<(Hash, String)> {
+        hashes
+            .iter()
+            .map(|hash| {
+                let block = peer.get_block_by_hash(hash).unwrap();
+                (*hash, block.get_payload().clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sync_session_steps() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        let mut peer = chain.clone_truncated(1).unwrap();
+        peer.add_block("third block".to_string())?;
+        peer.add_block("fourth block".to_string())?;
+
+        let mut session = chain.sync_session();
+        assert_eq!(SyncState::RequestHeaders, session.state());
+        assert_eq!(Ok(SyncAction::RequestHeaders), session.step(&mut chain));
+        // no answer yet, the request is repeated
+        assert_eq!(Ok(SyncAction::RequestHeaders), session.step(&mut chain));
+        assert_eq!(SyncState::AwaitingHeaders, session.state());
+
+        session.receive_headers(peer_headers(&peer));
+        assert_eq!(SyncState::ValidateHeaders, session.state());
+        assert_eq!(Ok(SyncAction::Continue), session.step(&mut chain));
+        assert_eq!(SyncState::RequestBodies, session.state());
+
+        let missing = match session.step(&mut chain) {
+            Ok(SyncAction::RequestBodies(missing)) => missing,
+            other => panic!("expected a body request, got {other:?}"),
+        };
+        let expected: Vec<Hash> = peer.get_blocks()[2..].iter().map(Block::get_hash).collect();
+        assert_eq!(expected, missing);
+
+        // a partial answer only asks for the rest
+        session
+            .receive_bodies(peer_bodies(&peer, &missing[1..]))
+            .unwrap();
+        assert_eq!(SyncState::AwaitingBodies, session.state());
+        assert_eq!(
+            Ok(SyncAction::RequestBodies(missing[..1].to_vec())),
+            session.step(&mut chain)
+        );
+        session
+            .receive_bodies(peer_bodies(&peer, &missing[..1]))
+            .unwrap();
+        assert_eq!(SyncState::Assemble, session.state());
+
+        assert_eq!(Ok(SyncAction::Continue), session.step(&mut chain));
+        assert_eq!(SyncState::Commit, session.state());
+        assert_eq!(1, chain.height());
+
+        assert_eq!(Ok(SyncAction::Done), session.step(&mut chain));
+        assert_eq!(SyncState::Done, session.state());
+        assert_eq!(peer.get_blocks(), chain.get_blocks());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_session_shorter_peer() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        let peer = Chain::default();
+
+        let mut session = chain.sync_session();
+        session.step(&mut chain).unwrap();
+        session.receive_headers(peer_headers(&peer));
+
+        assert_eq!(Ok(SyncAction::Done), session.step(&mut chain));
+        assert_eq!(2, chain.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_session_rejects_other_network() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        let mut peer = Chain::new_network("private net");
+        peer.add_block("second block".to_string())?;
+
+        let mut session = chain.sync_session();
+        session.step(&mut chain).unwrap();
+        session.receive_headers(peer_headers(&peer));
+
+        assert_eq!(
+            Err(ValidationError::BadGenesisBlock),
+            session.step(&mut chain)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_session_rejects_swapped_body() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        let mut peer = Chain::default();
+        peer.add_block("second block".to_string())?;
+
+        let mut session = chain.sync_session();
+        session.step(&mut chain).unwrap();
+        session.receive_headers(peer_headers(&peer));
+        session.step(&mut chain).unwrap();
+        session.step(&mut chain).unwrap();
+
+        let hash = peer.tip_hash().unwrap();
+        assert_eq!(
+            Err(ValidationError::InvalidHash),
+            session.receive_bodies(vec![(hash, "swapped block".to_string())])
+        );
+        assert_eq!(SyncState::AwaitingBodies, session.state());
+        assert_eq!(1, chain.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,367 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    block::{Block, MAX_PAYLOAD_BYTES},
+    chain::ValidationError,
+};
+
+/// Largest record accepted when streaming, a block with the largest payload plus room for its
+/// fixed-size fields.
+const MAX_RECORD_LEN: usize = MAX_PAYLOAD_BYTES + 256;
+
+/// Errors reading or writing stored blocks: the write-ahead log, snapshots and imports.
+#[derive(Debug)]
+pub enum WalError {
+    Io(io::Error),
+    CorruptedRecord,
+    InvalidChain(ValidationError),
+}
+
+impl From<io::Error> for WalError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Append-only log of the blocks added to a chain. Each record is an encoded block prefixed with
+/// its length as a little endian u32.
+pub(crate) struct Wal {
+    file: File,
+    path: PathBuf,
+}
+
+impl Wal {
+    /// Opens the log for appending, dropping anything past `intact_len` (a record cut short by a
+    /// crash) so new records don't end up behind garbage.
+    pub(crate) fn open(path: &Path, intact_len: u64) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.set_len(intact_len)?;
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub(crate) fn append(&mut self, block: &Block) -> io::Result<()> {
+        self.file.write_all(&record(block))?;
+        self.file.sync_data()
+    }
+
+    /// Replaces every record with `blocks`, for changes appending can't express: redacted
+    /// payloads have to be gone from the disk, dropped blocks must not come back on replay.
+    pub(crate) fn rewrite(&mut self, blocks: &[Block]) -> io::Result<()> {
+        Self::write_snapshot(&self.path, blocks)?;
+        self.file = OpenOptions::new().append(true).open(&self.path)?;
+        Ok(())
+    }
+
+    /// Writes `blocks` as a fresh log at `path`, replacing it only once fully written so a crash
+    /// midway leaves the previous snapshot intact.
+    pub(crate) fn write_snapshot(path: &Path, blocks: &[Block]) -> io::Result<()> {
+        let tmp_path = path.with_extension("tmp");
+
+        let mut file = File::create(&tmp_path)?;
+        write_records(&mut file, blocks)?;
+        file.sync_data()?;
+
+        fs::rename(tmp_path, path)
+    }
+
+    /// Reads back every block in the log along with the length of its intact part. A missing
+    /// log is an empty one.
+    pub(crate) fn read(path: &Path) -> Result<(Vec<Block>, u64), WalError> {
+        let mut bytes = vec![];
+        match File::open(path) {
+            Ok(mut file) => file.read_to_end(&mut bytes)?,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok((vec![], 0)),
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut blocks = vec![];
+        let mut offset = 0;
+        while let Some(len) = bytes.get(offset..offset + 4) {
+            let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+            let record = match bytes.get(offset + 4..offset + 4 + len) {
+                Some(record) => record,
+                // the last write didn't make it to disk entirely
+                None => break,
+            };
+            blocks.push(Block::decode(record).ok_or(WalError::CorruptedRecord)?);
+            offset += 4 + len;
+        }
+
+        Ok((blocks, offset as u64))
+    }
+}
+
+pub(crate) fn write_records<W: Write>(mut writer: W, blocks: &[Block]) -> io::Result<()> {
+    blocks
+        .iter()
+        .try_for_each(|block| writer.write_all(&record(block)))?;
+    writer.flush()
+}
+
+/// Reads the next record of a stream, `None` once it ends cleanly. Unlike in the log, a record
+/// cut short means the stream is corrupted.
+pub(crate) fn read_record<R: Read>(reader: &mut R) -> Result<Option<Block>, WalError> {
+    let mut len = vec![];
+    reader.take(4).read_to_end(&mut len)?;
+    let len = match len.len() {
+        0 => return Ok(None),
+        4 => u32::from_le_bytes(len.try_into().unwrap()) as usize,
+        _ => return Err(WalError::CorruptedRecord),
+    };
+    if len > MAX_RECORD_LEN {
+        return Err(WalError::CorruptedRecord);
+    }
+
+    let mut record = vec![0; len];
+    match reader.read_exact(&mut record) {
+        Ok(()) => {}
+        Err(error) if error.kind() == ErrorKind::UnexpectedEof => {
+            return Err(WalError::CorruptedRecord)
+        }
+        Err(error) => return Err(error.into()),
+    }
+
+    Block::decode(&record)
+        .map(Some)
+        .ok_or(WalError::CorruptedRecord)
+}
+
+fn record(block: &Block) -> Vec<u8> {
+    let encoded = block.encode();
+    let mut record = (encoded.len() as u32).to_le_bytes().to_vec();
+    record.extend(encoded);
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::OpenOptions,
+        io::{Cursor, Write},
+    };
+
+    use crate::{Chain, MiningError, ValidationError, WalError};
+
+    #[test]
+    fn test_replay_after_crash() -> Result<(), MiningError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.wal");
+
+        let mut chain = Chain::open_with_wal(&path).unwrap();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+        let blocks = chain.get_blocks();
+        // no snapshot is ever written, only the log survives
+        drop(chain);
+
+        let recovered = Chain::replay_wal(&path).unwrap();
+        assert_eq!(blocks, recovered.get_blocks());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reopen_keeps_logging() -> Result<(), MiningError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.wal");
+
+        let mut chain = Chain::open_with_wal(&path).unwrap();
+        chain.add_block("second block".to_string())?;
+        drop(chain);
+
+        let mut chain = Chain::open_with_wal(&path).unwrap();
+        chain.add_block("third block".to_string())?;
+        drop(chain);
+
+        let recovered = Chain::replay_wal(&path).unwrap();
+        assert_eq!(3, recovered.len());
+        assert_eq!(Ok(()), recovered.validate());
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_drops_torn_record() -> Result<(), MiningError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.wal");
+
+        let mut chain = Chain::open_with_wal(&path).unwrap();
+        chain.add_block("second block".to_string())?;
+        drop(chain);
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[200, 0, 0, 0, 1, 2, 3]).unwrap();
+        drop(file);
+
+        let mut chain = Chain::open_with_wal(&path).unwrap();
+        assert_eq!(2, chain.len());
+        chain.add_block("third block".to_string())?;
+        drop(chain);
+
+        assert_eq!(3, Chain::replay_wal(&path).unwrap().len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_after_reorg() -> Result<(), MiningError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.wal");
+
+        let mut chain = Chain::open_with_wal(&path).unwrap();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+
+        let mut incoming = Chain::default();
+        incoming.add_block("forked second block".to_string())?;
+        incoming.add_block("forked third block".to_string())?;
+        incoming.add_block("forked fourth block".to_string())?;
+        chain.accept(incoming).unwrap();
+        let blocks = chain.get_blocks();
+        drop(chain);
+
+        assert_eq!(blocks, Chain::replay_wal(&path).unwrap().get_blocks());
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_and_load() -> Result<(), MiningError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.bin");
+
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+        chain.save_to_path(&path).unwrap();
+
+        assert_eq!(
+            chain.get_blocks(),
+            Chain::load_from_path(&path).unwrap().get_blocks()
+        );
+        assert!(Chain::load_from_path(dir.path().join("missing.bin")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_streaming() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        for i in 1..1000 {
+            chain.add_block(format!("block {i}"))?;
+        }
+
+        let mut stream = vec![];
+        chain.export(&mut stream).unwrap();
+
+        let imported = Chain::import_streaming(Cursor::new(stream)).unwrap();
+        assert_eq!(1000, imported.len());
+        assert_eq!(chain.get_blocks(), imported.get_blocks());
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_streaming_aborts_early() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        for i in 1..1000 {
+            chain.add_block(format!("block {i}"))?;
+        }
+        let mut stream = vec![];
+        chain.export(&mut stream).unwrap();
+        let start = stream
+            .windows(9)
+            .position(|window| window == b"block 500")
+            .unwrap();
+        stream[start..start + 9].copy_from_slice(b"tampered!");
+        let stream_len = stream.len() as u64;
+
+        let mut reader = Cursor::new(stream);
+        assert!(matches!(
+            Chain::import_streaming(&mut reader),
+            Err(WalError::InvalidChain(ValidationError::InvalidHash))
+        ));
+        assert!(reader.position() < stream_len);
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_streaming_refuses_redacted_blocks() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.redact(1).unwrap();
+
+        let mut stream = vec![];
+        chain.export(&mut stream).unwrap();
+        // forge the stored hash, found after the record length, timestamp and previous hash
+        stream[52..84].copy_from_slice(&[0xab; 32]);
+
+        assert!(matches!(
+            Chain::import_streaming(Cursor::new(stream)),
+            Err(WalError::InvalidChain(ValidationError::InvalidHash))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_from_path_keeps_redactions() -> Result<(), MiningError> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.bin");
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+        chain.redact(1).unwrap();
+        chain.save_to_path(&path).unwrap();
+
+        let loaded = Chain::load_from_path(&path).unwrap();
+        assert_eq!(chain.get_blocks(), loaded.get_blocks());
+        assert!(loaded.get_blocks()[1].is_redacted());
+        Ok(())
+    }
+
+    #[test]
+    fn test_private_network_round_trip() -> Result<(), MiningError> {
+        let dir = tempfile::tempdir().unwrap();
+        let wal_path = dir.path().join("chain.wal");
+        let config = Chain::new_network("private net").get_config().clone();
+
+        let mut chain = Chain::open_with_wal_and_config(&wal_path, config.clone()).unwrap();
+        chain.add_block("second block".to_string())?;
+        chain.add_block("third block".to_string())?;
+        let blocks = chain.get_blocks();
+        drop(chain);
+        assert!(Chain::replay_wal(&wal_path).is_err());
+        let replayed = Chain::replay_wal_with_config(&wal_path, config.clone()).unwrap();
+        assert_eq!(blocks, replayed.get_blocks());
+
+        let mut stream = vec![];
+        replayed.export(&mut stream).unwrap();
+        assert!(Chain::import_streaming(Cursor::new(&stream)).is_err());
+        let imported =
+            Chain::import_streaming_with_config(Cursor::new(&stream), config.clone()).unwrap();
+        assert_eq!(blocks, imported.get_blocks());
+
+        let path = dir.path().join("chain.bin");
+        imported.save_to_path(&path).unwrap();
+        assert!(Chain::load_from_path(&path).is_err());
+        let loaded = Chain::load_from_path_with_config(&path, config).unwrap();
+        assert_eq!(blocks, loaded.get_blocks());
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_streaming_truncated() -> Result<(), MiningError> {
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string())?;
+
+        let mut stream = vec![];
+        chain.export(&mut stream).unwrap();
+        stream.pop();
+
+        assert!(matches!(
+            Chain::import_streaming(Cursor::new(stream)),
+            Err(WalError::CorruptedRecord)
+        ));
+        Ok(())
+    }
+}
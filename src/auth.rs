@@ -0,0 +1,66 @@
+//! API-key protection for the routes writing to the chain: once an `api_key` is configured (e.g.
+//! `ROCKET_API_KEY`), they need an `Authorization: Bearer <api_key>` header. Reads stay open, and
+//! so does everything when no key is configured.
+
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+
+pub const AUTHORIZATION_HEADER: &str = "Authorization";
+
+/// The key write requests have to present, if any.
+pub struct ApiKey(pub Option<String>);
+
+impl ApiKey {
+    fn accepts(&self, header: Option<&str>) -> bool {
+        let key = match &self.0 {
+            Some(key) => key,
+            None => return true,
+        };
+        match header.and_then(|header| header.strip_prefix("Bearer ")) {
+            Some(token) => constant_time_eq(token.as_bytes(), key.as_bytes()),
+            None => false,
+        }
+    }
+}
+
+/// Request guard for write routes, failing with 401 when the request doesn't carry the key.
+pub struct Authorized;
+
+#[derive(Debug)]
+pub struct Unauthorized;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Authorized {
+    type Error = Unauthorized;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let api_key = request.rocket().state::<ApiKey>().expect("managed api key");
+        if api_key.accepts(request.headers().get_one(AUTHORIZATION_HEADER)) {
+            Outcome::Success(Self)
+        } else {
+            Outcome::Failure((Status::Unauthorized, Unauthorized))
+        }
+    }
+}
+
+// doesn't stop at the first differing byte, so response times don't leak how much of a guessed
+// key was right
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApiKey;
+
+    #[test]
+    fn test_accepts_bearer_key_only() {
+        let api_key = ApiKey(Some("secret".to_string()));
+
+        assert!(api_key.accepts(Some("Bearer secret")));
+        assert!(!api_key.accepts(Some("Bearer secrets")));
+        assert!(!api_key.accepts(Some("secret")));
+        assert!(!api_key.accepts(None));
+        assert!(ApiKey(None).accepts(None));
+    }
+}
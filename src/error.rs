@@ -0,0 +1,108 @@
+//! JSON error envelope shared by every route, `{ "error": { "code": 404, "message": "..." } }`,
+//! and the catchers answering with it instead of Rocket's HTML error pages.
+
+use rocket::http::Status;
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+use rocket::serde::Serialize;
+use rocket::{Catcher, Request};
+
+#[derive(Serialize)]
+pub struct ErrorBody {
+    error: ErrorDetail,
+}
+
+#[derive(Serialize)]
+struct ErrorDetail {
+    code: u16,
+    message: String,
+}
+
+impl ErrorBody {
+    pub fn message(&self) -> &str {
+        &self.error.message
+    }
+}
+
+/// Error response of a route, the status and the same envelope the catchers use.
+pub type ApiError = Custom<Json<ErrorBody>>;
+
+pub fn api_error(status: Status, message: impl Into<String>) -> ApiError {
+    Custom(
+        status,
+        Json(ErrorBody {
+            error: ErrorDetail {
+                code: status.code,
+                message: message.into(),
+            },
+        }),
+    )
+}
+
+fn caught(status: Status) -> Json<ErrorBody> {
+    api_error(status, status.reason().unwrap_or("Unknown Error")).1
+}
+
+#[catch(401)]
+fn unauthorized() -> Json<ErrorBody> {
+    caught(Status::Unauthorized)
+}
+
+#[catch(404)]
+fn not_found(request: &Request) -> Json<ErrorBody> {
+    api_error(Status::NotFound, format!("nothing at {}", request.uri())).1
+}
+
+#[catch(422)]
+fn unprocessable_entity() -> Json<ErrorBody> {
+    caught(Status::UnprocessableEntity)
+}
+
+#[catch(500)]
+fn internal_error() -> Json<ErrorBody> {
+    caught(Status::InternalServerError)
+}
+
+pub fn catchers() -> Vec<Catcher> {
+    catchers![
+        unauthorized,
+        not_found,
+        unprocessable_entity,
+        internal_error
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::http::{ContentType, Status};
+    use rocket::local::blocking::Client;
+    use rocket::serde::json::Value;
+
+    #[test]
+    fn test_not_found_is_json() {
+        let client = Client::tracked(crate::rocket()).expect("valid rocket instance");
+
+        let response = client.get("/nowhere").dispatch();
+        assert_eq!(Status::NotFound, response.status());
+        assert_eq!(Some(ContentType::JSON), response.content_type());
+
+        let body: Value = response.into_json().expect("error envelope");
+        assert_eq!(404, body["error"]["code"]);
+        assert_eq!("nothing at /nowhere", body["error"]["message"]);
+    }
+
+    #[test]
+    fn test_unprocessable_entity_is_json() {
+        let client = Client::tracked(crate::rocket()).expect("valid rocket instance");
+
+        let response = client
+            .post("/blocks")
+            .header(ContentType::JSON)
+            .body(r#"{ "nonsense": true }"#)
+            .dispatch();
+        assert_eq!(Status::UnprocessableEntity, response.status());
+
+        let body: Value = response.into_json().expect("error envelope");
+        assert_eq!(422, body["error"]["code"]);
+    }
+}
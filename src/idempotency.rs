@@ -0,0 +1,120 @@
+//! Retry-safe mining: clients send an `Idempotency-Key` header with `POST /blocks` (or
+//! `POST /chains/<network_id>/blocks`, or a `mineBlock` call to `/rpc`) and retries carrying the
+//! same key get the block mined the first time instead of a new one.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chain_rs_lib::Hash;
+use rocket::request::{FromRequest, Outcome, Request};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// How long a key is remembered after the block it mined.
+pub const DEFAULT_KEY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How many keys are remembered at most, the oldest being dropped first.
+pub const DEFAULT_KEY_CAPACITY: usize = 10_000;
+
+/// The `Idempotency-Key` header of a request, if any.
+pub struct IdempotencyKey(pub Option<String>);
+
+impl IdempotencyKey {
+    /// The key as stored in the cache, prefixed with the chain it mines on (`""` for the main
+    /// chain, the network id otherwise) so the same key can be used on each chain.
+    pub fn scoped(self, scope: &str) -> Option<String> {
+        self.0.map(|key| format!("{scope}/{key}"))
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IdempotencyKey {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(Self(
+            request
+                .headers()
+                .get_one(IDEMPOTENCY_KEY_HEADER)
+                .map(str::to_string),
+        ))
+    }
+}
+
+/// Recently used keys and the hash of the block each one mined.
+pub struct IdempotencyCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<String, (Instant, Hash)>>,
+}
+
+impl IdempotencyCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<Hash> {
+        let entries = self.entries.lock().expect("locked idempotency keys");
+        entries
+            .get(key)
+            .filter(|(inserted, _)| inserted.elapsed() < self.ttl)
+            .map(|(_, hash)| *hash)
+    }
+
+    pub fn insert(&self, key: String, hash: Hash) {
+        let mut entries = self.entries.lock().expect("locked idempotency keys");
+        entries.retain(|_, (inserted, _)| inserted.elapsed() < self.ttl);
+
+        while entries.len() >= self.capacity {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, (inserted, _))| *inserted)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(oldest) => entries.remove(&oldest),
+                None => break,
+            };
+        }
+
+        entries.insert(key, (Instant::now(), hash));
+    }
+}
+
+impl Default for IdempotencyCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_KEY_TTL, DEFAULT_KEY_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chain_rs_lib::Hash;
+
+    use super::IdempotencyCache;
+
+    #[test]
+    fn test_expired_keys_are_forgotten() {
+        let cache = IdempotencyCache::new(Duration::ZERO, 10);
+        cache.insert("key".to_string(), Hash::default());
+
+        assert_eq!(None, cache.get("key"));
+    }
+
+    #[test]
+    fn test_capacity_drops_oldest() {
+        let cache = IdempotencyCache::new(Duration::from_secs(60), 1);
+        cache.insert("first".to_string(), Hash::default());
+        cache.insert("second".to_string(), Hash::default());
+
+        assert_eq!(None, cache.get("first"));
+        assert_eq!(Some(Hash::default()), cache.get("second"));
+    }
+}
@@ -1,40 +1,776 @@
 #[macro_use]
 extern crate rocket;
 
+mod auth;
+mod error;
+mod idempotency;
+mod rpc;
+
 use std::borrow::BorrowMut;
-use std::sync::Mutex;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use chain_rs_lib::{Block, Chain};
+use auth::{ApiKey, Authorized};
+use chain_rs_lib::{Block, Chain, ChainRegistry, Hash, MiningError};
+use error::{api_error, ApiError};
+use idempotency::{IdempotencyCache, IdempotencyKey};
+use rocket::http::{Header, Status as HttpStatus};
 use rocket::serde::json::Json;
-use rocket::serde::Deserialize;
-use rocket::State;
+use rocket::serde::{Deserialize, Serialize};
+use rocket::{Build, Rocket, State};
+
+struct BlockChain(Arc<Mutex<Chain>>);
+
+/// Extra chains served under `/chains/<network_id>`, one per id listed in `networks`.
+struct Networks(Mutex<ChainRegistry>);
+
+/// Where the chain is saved once the server has shut down gracefully (e.g. on SIGINT), so it can
+/// be loaded back on the next start.
+#[derive(Clone)]
+struct PersistOnShutdown {
+    path: PathBuf,
+}
+
+impl PersistOnShutdown {
+    fn on_shutdown(&self, chain: &Mutex<Chain>) -> io::Result<()> {
+        let lock = chain.lock().expect("locked blockchain");
+        lock.save_to_path(&self.path)
+    }
+}
 
-struct BlockChain(Mutex<Chain>);
+const DEFAULT_STALE_AFTER_SECS: u64 = 600;
+
+/// How long a chain can go without a new block before `/status` reports it as stale.
+struct StaleAfter(Duration);
 
 #[derive(Deserialize)]
 struct Payload<'a> {
     value: &'a str,
 }
 
-#[get("/blocks")]
-fn get_blocks(chain_state: &State<BlockChain>) -> Json<Vec<Block>> {
+#[derive(Serialize)]
+struct Status {
+    network: String,
+    height: u64,
+    valid: bool,
+    total_size_bytes: usize,
+    reorg_count: u64,
+    max_reorg_depth_seen: u64,
+    stale: bool,
+}
+
+impl Status {
+    fn of(chain: &Chain, stale_after: &StaleAfter) -> Self {
+        Self {
+            network: chain.network_label(),
+            height: chain.height(),
+            valid: chain.is_valid(),
+            total_size_bytes: chain.total_size_bytes(),
+            reorg_count: chain.reorg_count(),
+            max_reorg_depth_seen: chain.max_reorg_depth_seen(),
+            stale: chain.is_stale(stale_after.0),
+        }
+    }
+}
+
+#[get("/status")]
+fn get_status(chain_state: &State<BlockChain>, stale_after: &State<StaleAfter>) -> Json<Status> {
     let lock = chain_state.0.lock().expect("locked blockchain");
-    let blocks = lock.get_blocks();
-    Json(blocks)
+    Json(Status::of(&lock, stale_after))
+}
+
+/// All blocks, or only those above `since_height` or after the block `since_hash` for clients
+/// polling for new ones. An unknown `since_hash` is a 404.
+#[get("/blocks?<since_height>&<since_hash>")]
+fn get_blocks(
+    since_height: Option<u64>,
+    since_hash: Option<&str>,
+    chain_state: &State<BlockChain>,
+) -> Option<Json<Vec<Block>>> {
+    let lock = chain_state.0.lock().expect("locked blockchain");
+    let blocks = match (since_hash, since_height) {
+        (Some(hash), _) => {
+            let height = lock.height_of(&Hash::from_hex(hash).ok()?)?;
+            lock.blocks_after(height)
+        }
+        (None, Some(height)) => lock.blocks_after(height),
+        (None, None) => lock.get_blocks(),
+    };
+    Some(Json(blocks))
+}
+
+/// Default for the `n` of `/blocks/latest`.
+const LATEST_BLOCKS: usize = 10;
+
+/// The `n` most recent blocks, newest first.
+#[get("/blocks/latest?<n>")]
+fn get_latest_blocks(n: Option<usize>, chain_state: &State<BlockChain>) -> Json<Vec<Block>> {
+    let lock = chain_state.0.lock().expect("locked blockchain");
+    let blocks = lock.last_n(n.unwrap_or(LATEST_BLOCKS));
+    Json(blocks.into_iter().cloned().collect())
+}
+
+#[get("/tip")]
+fn get_tip(chain_state: &State<BlockChain>) -> Option<Json<Block>> {
+    let lock = chain_state.0.lock().expect("locked blockchain");
+    lock.tip().cloned().map(Json)
+}
+
+/// Response to mining a block, `true` as before plus the block hash in a header so retries can
+/// be told apart from new blocks.
+#[derive(Responder)]
+struct Mined {
+    inner: Json<bool>,
+    hash: Header<'static>,
+}
+
+impl Mined {
+    fn new(hash: Hash) -> Self {
+        Self {
+            inner: Json(true),
+            hash: Header::new("X-Block-Hash", hash.to_hex()),
+        }
+    }
+}
+
+/// Blocks refused by the chain rules are the client's fault, anything else (e.g. a failed log
+/// write) is ours.
+fn mining_error(error: MiningError) -> ApiError {
+    let status = match error {
+        MiningError::InvalidBlock(_) | MiningError::Invalid(_) => HttpStatus::UnprocessableEntity,
+        MiningError::NoPrev | MiningError::WalWrite(_) => HttpStatus::InternalServerError,
+    };
+    api_error(status, format!("could not mine block: {error:?}"))
+}
+
+/// Mines `value` into a new block of `chain` and returns its hash, shared by `POST /blocks`,
+/// `POST /chains/<network_id>/blocks` and the `mineBlock` JSON-RPC method. Empty or
+/// whitespace-only values are refused unless `allow_empty` is set, and a retry carrying an
+/// already used `key` gets the block mined the first time.
+fn mine(
+    chain: &mut Chain,
+    value: &str,
+    allow_empty: bool,
+    key: Option<String>,
+    idempotency: &IdempotencyCache,
+) -> Result<Hash, ApiError> {
+    if value.trim().is_empty() && !allow_empty {
+        return Err(api_error(
+            HttpStatus::BadRequest,
+            "payload must not be empty",
+        ));
+    }
+
+    if let Some(hash) = key.as_deref().and_then(|key| idempotency.get(key)) {
+        return Ok(hash);
+    }
+
+    chain.add_block(value.to_string()).map_err(mining_error)?;
+    let hash = chain.tip_hash().expect("mined block");
+
+    if let Some(key) = key {
+        idempotency.insert(key, hash);
+    }
+    Ok(hash)
+}
+
+/// Mines `value` into a new block. Empty or whitespace-only values are refused unless
+/// `allow_empty` is set.
+#[post("/blocks?<allow_empty>", data = "<payload>")]
+fn mine_block(
+    payload: Json<Payload>,
+    allow_empty: Option<bool>,
+    _authorized: Authorized,
+    key: IdempotencyKey,
+    chain_state: &State<BlockChain>,
+    idempotency: &State<IdempotencyCache>,
+) -> Result<Mined, ApiError> {
+    let mut lock = chain_state.0.lock().expect("locked blockchain");
+    mine(
+        lock.borrow_mut(),
+        payload.value,
+        allow_empty.unwrap_or(false),
+        key.scoped(""),
+        idempotency,
+    )
+    .map(Mined::new)
+}
+
+#[derive(Serialize)]
+struct MinedBatch {
+    height: u64,
+    hashes: Vec<Hash>,
 }
 
-#[post("/blocks", data = "<payload>")]
-fn mine_block(payload: Json<Payload>, chain_state: &State<BlockChain>) -> Json<bool> {
+/// Mines every value into a block of its own, in order. Either all of them end up in the chain
+/// or, if any is refused, none does.
+#[post("/blocks/batch", data = "<values>")]
+fn mine_blocks(
+    values: Json<Vec<String>>,
+    _authorized: Authorized,
+    chain_state: &State<BlockChain>,
+) -> Result<Json<MinedBatch>, ApiError> {
+    if values.iter().any(|value| value.trim().is_empty()) {
+        return Err(api_error(
+            HttpStatus::BadRequest,
+            "payloads must not be empty",
+        ));
+    }
+
     let mut lock = chain_state.0.lock().expect("locked blockchain");
-    lock.borrow_mut()
-        .add_block(payload.value.to_string())
-        .unwrap();
-    Json(true)
+    let hashes = lock.add_blocks(values.into_inner()).map_err(mining_error)?;
+    Ok(Json(MinedBatch {
+        height: lock.height(),
+        hashes,
+    }))
+}
+
+/// JSON-RPC endpoint, open to reads; methods writing to the chain need the API key.
+/// `mineBlock` honours the `Idempotency-Key` header like `POST /blocks`.
+#[post("/rpc", data = "<body>")]
+fn json_rpc(
+    body: &str,
+    authorized: Option<Authorized>,
+    key: IdempotencyKey,
+    chain_state: &State<BlockChain>,
+    stale_after: &State<StaleAfter>,
+    idempotency: &State<IdempotencyCache>,
+) -> Json<rpc::Response> {
+    let mut lock = chain_state.0.lock().expect("locked blockchain");
+    Json(rpc::handle(
+        body,
+        &mut lock,
+        stale_after,
+        authorized.is_some(),
+        key.scoped(""),
+        idempotency,
+    ))
+}
+
+#[get("/chains/<network_id>/status")]
+fn get_network_status(
+    network_id: &str,
+    networks: &State<Networks>,
+    stale_after: &State<StaleAfter>,
+) -> Option<Json<Status>> {
+    let lock = networks.0.lock().expect("locked registry");
+    lock.get(network_id)
+        .map(|chain| Json(Status::of(chain, stale_after)))
+}
+
+#[get("/chains/<network_id>/blocks")]
+fn get_network_blocks(network_id: &str, networks: &State<Networks>) -> Option<Json<Vec<Block>>> {
+    let lock = networks.0.lock().expect("locked registry");
+    lock.get(network_id).map(Chain::get_blocks).map(Json)
+}
+
+/// Like `POST /blocks`, on the chain of `network_id`.
+#[post("/chains/<network_id>/blocks?<allow_empty>", data = "<payload>")]
+fn mine_network_block(
+    network_id: &str,
+    payload: Json<Payload>,
+    allow_empty: Option<bool>,
+    _authorized: Authorized,
+    key: IdempotencyKey,
+    networks: &State<Networks>,
+    idempotency: &State<IdempotencyCache>,
+) -> Result<Mined, ApiError> {
+    let mut lock = networks.0.lock().expect("locked registry");
+    lock.dispatch(network_id, |chain| {
+        mine(
+            chain,
+            payload.value,
+            allow_empty.unwrap_or(false),
+            key.scoped(network_id),
+            idempotency,
+        )
+        .map(Mined::new)
+    })
+    .unwrap_or_else(|| {
+        Err(api_error(
+            HttpStatus::NotFound,
+            format!("unknown network {network_id}"),
+        ))
+    })
+}
+
+#[rocket::main]
+async fn main() -> Result<(), Box<rocket::Error>> {
+    let rocket = rocket().ignite().await?;
+    let chain = rocket
+        .state::<BlockChain>()
+        .expect("managed blockchain")
+        .0
+        .clone();
+    let persist = rocket.state::<PersistOnShutdown>().cloned();
+
+    rocket.launch().await?;
+
+    if let Some(persist) = persist {
+        match persist.on_shutdown(&chain) {
+            Ok(()) => println!("chain saved to {}", persist.path.display()),
+            Err(error) => eprintln!("could not save chain: {error}"),
+        }
+    }
+    Ok(())
+}
+
+fn rocket() -> Rocket<Build> {
+    build(rocket::build())
 }
 
-#[launch]
-fn rocket() -> rocket::Rocket<rocket::Build> {
-    rocket::build()
-        .manage(BlockChain(Mutex::new(Chain::default())))
-        .mount("/", routes![get_blocks, mine_block])
+fn build(rocket: Rocket<Build>) -> Rocket<Build> {
+    // `chain_path` (e.g. `ROCKET_CHAIN_PATH`) enables loading the chain on start and saving it on shutdown
+    let mut chain_path: Option<PathBuf> = rocket.figment().extract_inner("chain_path").ok();
+    let chain = match &chain_path {
+        Some(path) if path.exists() => match Chain::load_from_path(path) {
+            Ok(chain) => chain,
+            Err(error) => {
+                // start fresh, but leave the unreadable file alone rather than overwriting it
+                eprintln!(
+                    "could not load chain from {}, starting a fresh one without persistence: {error:?}",
+                    path.display()
+                );
+                chain_path = None;
+                Chain::default()
+            }
+        },
+        _ => Chain::default(),
+    };
+
+    // `networks` (e.g. `ROCKET_NETWORKS=[alpha,beta]`) hosts one independent chain per network id
+    let network_ids: Vec<String> = rocket
+        .figment()
+        .extract_inner("networks")
+        .unwrap_or_default();
+    let mut networks = ChainRegistry::default();
+    for network_id in network_ids {
+        let chain = Chain::new_network(&network_id);
+        networks.insert(network_id, chain);
+    }
+
+    // `api_key` (e.g. `ROCKET_API_KEY`) restricts the write routes to requests bearing it
+    let api_key: Option<String> = rocket.figment().extract_inner("api_key").ok();
+
+    // `stale_after_secs` (e.g. `ROCKET_STALE_AFTER_SECS`) is how old the tip can get before
+    // `/status` flags the chain as stale
+    let stale_after = rocket
+        .figment()
+        .extract_inner("stale_after_secs")
+        .unwrap_or(DEFAULT_STALE_AFTER_SECS);
+
+    let rocket = rocket
+        .manage(StaleAfter(Duration::from_secs(stale_after)))
+        .manage(BlockChain(Arc::new(Mutex::new(chain))))
+        .manage(Networks(Mutex::new(networks)))
+        .manage(IdempotencyCache::default())
+        .manage(ApiKey(api_key))
+        .register("/", error::catchers())
+        .mount(
+            "/",
+            routes![
+                get_blocks,
+                get_latest_blocks,
+                mine_block,
+                mine_blocks,
+                get_status,
+                get_tip,
+                json_rpc,
+                get_network_status,
+                get_network_blocks,
+                mine_network_block
+            ],
+        );
+
+    match chain_path {
+        Some(path) => rocket.manage(PersistOnShutdown { path }),
+        None => rocket,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use chain_rs_lib::{Block, Chain, Hash};
+    use rocket::http::{ContentType, Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::serde::json::Value;
+
+    use super::PersistOnShutdown;
+
+    #[test]
+    fn test_status_network_label() {
+        let figment = rocket::Config::figment().merge(("networks", ["alpha"]));
+        let client =
+            Client::tracked(super::build(rocket::custom(figment))).expect("valid rocket instance");
+        let network = |uri: &str| -> String {
+            let status: Value = client.get(uri.to_string()).dispatch().into_json().unwrap();
+            status["network"]
+                .as_str()
+                .expect("network label")
+                .to_string()
+        };
+
+        assert_eq!(Chain::default().network_label(), network("/status"));
+        assert!(network("/chains/alpha/status").starts_with("alpha ("));
+    }
+
+    #[test]
+    fn test_status_stale() {
+        let client = Client::tracked(super::rocket()).expect("valid rocket instance");
+        let stale = || -> bool {
+            let status: Value = client.get("/status").dispatch().into_json().unwrap();
+            status["stale"].as_bool().expect("staleness")
+        };
+
+        // the genesis block is timestamped at the epoch
+        assert!(stale());
+        client
+            .post("/blocks")
+            .header(ContentType::JSON)
+            .body(r#"{ "value": "Hello world!" }"#)
+            .dispatch();
+        assert!(!stale());
+    }
+
+    #[test]
+    fn test_get_tip() {
+        let client = Client::tracked(super::rocket()).expect("valid rocket instance");
+
+        let response = client
+            .post("/blocks")
+            .header(ContentType::JSON)
+            .body(r#"{ "value": "Hello world!" }"#)
+            .dispatch();
+        assert_eq!(Status::Ok, response.status());
+
+        let response = client.get("/tip").dispatch();
+        assert_eq!(Status::Ok, response.status());
+        let tip: Block = response.into_json().expect("tip block");
+        assert_eq!("Hello world!", tip.get_payload());
+    }
+
+    #[test]
+    fn test_persist_on_shutdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let persist = PersistOnShutdown {
+            path: dir.path().join("chain.bin"),
+        };
+
+        let mut chain = Chain::default();
+        chain.add_block("second block".to_string()).unwrap();
+        chain.add_block("third block".to_string()).unwrap();
+        persist.on_shutdown(&Mutex::new(chain)).unwrap();
+
+        assert!(persist.path.exists());
+        assert_eq!(3, Chain::load_from_path(&persist.path).unwrap().len());
+    }
+
+    #[test]
+    fn test_corrupt_stored_chain_starts_fresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chain.bin");
+        std::fs::write(&path, b"not a chain").unwrap();
+
+        let figment = rocket::Config::figment().merge(("chain_path", &path));
+        let client =
+            Client::tracked(super::build(rocket::custom(figment))).expect("valid rocket instance");
+        let blocks: Vec<Block> = client.get("/blocks").dispatch().into_json().unwrap();
+
+        assert_eq!(1, blocks.len());
+        assert!(client.rocket().state::<PersistOnShutdown>().is_none());
+        assert_eq!(b"not a chain".to_vec(), std::fs::read(&path).unwrap());
+    }
+
+    #[test]
+    fn test_mine_block_idempotency_key() {
+        let client = Client::tracked(super::rocket()).expect("valid rocket instance");
+
+        let mine = || {
+            client
+                .post("/blocks")
+                .header(ContentType::JSON)
+                .header(Header::new("Idempotency-Key", "retry-me"))
+                .body(r#"{ "value": "Hello world!" }"#)
+                .dispatch()
+        };
+        let first = mine();
+        let retry = mine();
+
+        assert_eq!(Status::Ok, first.status());
+        assert_eq!(Status::Ok, retry.status());
+        assert_eq!(
+            first.headers().get_one("X-Block-Hash"),
+            retry.headers().get_one("X-Block-Hash")
+        );
+        assert_eq!(first.into_string(), retry.into_string());
+
+        let blocks: Vec<Block> = client
+            .get("/blocks")
+            .dispatch()
+            .into_json()
+            .expect("blocks");
+        assert_eq!(2, blocks.len());
+    }
+
+    #[test]
+    fn test_mine_blocks_batch() {
+        let client = Client::tracked(super::rocket()).expect("valid rocket instance");
+        let mine = |body: &str| {
+            client
+                .post("/blocks/batch")
+                .header(ContentType::JSON)
+                .body(body)
+                .dispatch()
+        };
+
+        let response = mine(r#"["first", "second", "third"]"#);
+        assert_eq!(Status::Ok, response.status());
+        let batch: Value = response.into_json().expect("mined batch");
+        assert_eq!(3, batch["height"]);
+        let hashes = batch["hashes"].as_array().expect("block hashes");
+        assert_eq!(3, hashes.len());
+
+        let tip: Value = client.get("/tip").dispatch().into_json().unwrap();
+        assert_eq!(tip["hash"], hashes[2]);
+
+        // nothing is mined when one of the values is refused
+        assert_eq!(Status::BadRequest, mine(r#"["fourth", " "]"#).status());
+        let blocks: Vec<Block> = client
+            .get("/blocks")
+            .dispatch()
+            .into_json()
+            .expect("blocks");
+        assert_eq!(4, blocks.len());
+    }
+
+    #[test]
+    fn test_mine_block_without_key() {
+        let client = Client::tracked(super::rocket()).expect("valid rocket instance");
+
+        for _ in 0..2 {
+            client
+                .post("/blocks")
+                .header(ContentType::JSON)
+                .body(r#"{ "value": "Hello world!" }"#)
+                .dispatch();
+        }
+
+        let blocks: Vec<Block> = client
+            .get("/blocks")
+            .dispatch()
+            .into_json()
+            .expect("blocks");
+        assert_eq!(3, blocks.len());
+    }
+
+    #[test]
+    fn test_networks_are_independent() {
+        let figment = rocket::Config::figment().merge(("networks", ["alpha", "beta"]));
+        let client =
+            Client::tracked(super::build(rocket::custom(figment))).expect("valid rocket instance");
+
+        let mine = |network_id: &str| {
+            client
+                .post(format!("/chains/{network_id}/blocks"))
+                .header(ContentType::JSON)
+                .body(r#"{ "value": "Hello world!" }"#)
+                .dispatch()
+        };
+        assert_eq!(Status::Ok, mine("alpha").status());
+        assert_eq!(Status::Ok, mine("beta").status());
+        assert_eq!(Status::Ok, mine("beta").status());
+        assert_eq!(Status::NotFound, mine("gamma").status());
+
+        let blocks = |network_id: &str| -> Vec<Block> {
+            client
+                .get(format!("/chains/{network_id}/blocks"))
+                .dispatch()
+                .into_json()
+                .expect("blocks")
+        };
+        let alpha = blocks("alpha");
+        let beta = blocks("beta");
+        assert_eq!(2, alpha.len());
+        assert_eq!(3, beta.len());
+        assert_ne!(alpha.last(), beta.last());
+
+        let default_chain: Vec<Block> = client
+            .get("/blocks")
+            .dispatch()
+            .into_json()
+            .expect("blocks");
+        assert_eq!(1, default_chain.len());
+    }
+
+    #[test]
+    fn test_mine_network_block_checks() {
+        let figment = rocket::Config::figment().merge(("networks", ["alpha", "beta"]));
+        let client =
+            Client::tracked(super::build(rocket::custom(figment))).expect("valid rocket instance");
+        let mine = |uri: &str, value: &str| {
+            client
+                .post(uri.to_string())
+                .header(ContentType::JSON)
+                .header(Header::new("Idempotency-Key", "retry-me"))
+                .body(format!(r#"{{ "value": "{value}" }}"#))
+                .dispatch()
+        };
+
+        assert_eq!(
+            Status::BadRequest,
+            mine("/chains/alpha/blocks", "  ").status()
+        );
+
+        let first = mine("/chains/alpha/blocks", "Hello world!");
+        let retry = mine("/chains/alpha/blocks", "Hello world!");
+        let other_network = mine("/chains/beta/blocks", "Hello world!");
+        let default_chain = mine("/blocks", "Hello world!");
+        let hash = |response: &rocket::local::blocking::LocalResponse| {
+            response
+                .headers()
+                .get_one("X-Block-Hash")
+                .map(str::to_string)
+        };
+        assert_eq!(hash(&first), hash(&retry));
+        assert_ne!(hash(&first), hash(&other_network));
+        assert_ne!(hash(&first), hash(&default_chain));
+
+        let blocks = |uri: &str| -> Vec<Block> {
+            client
+                .get(uri.to_string())
+                .dispatch()
+                .into_json()
+                .expect("blocks")
+        };
+        assert_eq!(2, blocks("/chains/alpha/blocks").len());
+        assert_eq!(2, blocks("/chains/beta/blocks").len());
+        assert_eq!(2, blocks("/blocks").len());
+    }
+
+    #[test]
+    fn test_get_blocks_since() {
+        let client = Client::tracked(super::rocket()).expect("valid rocket instance");
+        for value in ["second", "third"] {
+            client
+                .post("/blocks")
+                .header(ContentType::JSON)
+                .body(format!(r#"{{ "value": "{value}" }}"#))
+                .dispatch();
+        }
+        let blocks: Vec<Block> = client
+            .get("/blocks")
+            .dispatch()
+            .into_json()
+            .expect("blocks");
+
+        let since_height: Vec<Block> = client
+            .get("/blocks?since_height=1")
+            .dispatch()
+            .into_json()
+            .expect("blocks");
+        assert_eq!(blocks[2..], since_height[..]);
+
+        let since_hash: Vec<Block> = client
+            .get(format!("/blocks?since_hash={}", blocks[0].get_hash()))
+            .dispatch()
+            .into_json()
+            .expect("blocks");
+        assert_eq!(blocks[1..], since_hash[..]);
+
+        let response = client
+            .get(format!("/blocks?since_hash={}", Hash::default()))
+            .dispatch();
+        assert_eq!(Status::NotFound, response.status());
+    }
+
+    #[test]
+    fn test_mine_block_rejects_empty_payload() {
+        let client = Client::tracked(super::rocket()).expect("valid rocket instance");
+        let mine = |uri: &str, value: &str| {
+            client
+                .post(uri.to_string())
+                .header(ContentType::JSON)
+                .body(format!(r#"{{ "value": "{value}" }}"#))
+                .dispatch()
+        };
+
+        let response = mine("/blocks", "  ");
+        assert_eq!(Status::BadRequest, response.status());
+        assert_eq!(
+            Some(r#"{"error":{"code":400,"message":"payload must not be empty"}}"#.to_string()),
+            response.into_string()
+        );
+
+        assert_eq!(Status::Ok, mine("/blocks", "Hello world!").status());
+        assert_eq!(Status::Ok, mine("/blocks?allow_empty=true", "").status());
+
+        let blocks: Vec<Block> = client
+            .get("/blocks")
+            .dispatch()
+            .into_json()
+            .expect("blocks");
+        assert_eq!(3, blocks.len());
+    }
+
+    #[test]
+    fn test_mine_block_api_key() {
+        let figment = rocket::Config::figment().merge(("api_key", "secret"));
+        let client =
+            Client::tracked(super::build(rocket::custom(figment))).expect("valid rocket instance");
+        let mine = |authorization: Option<&str>| {
+            let request = client
+                .post("/blocks")
+                .header(ContentType::JSON)
+                .body(r#"{ "value": "Hello world!" }"#);
+            match authorization {
+                Some(authorization) => request
+                    .header(Header::new("Authorization", authorization.to_string()))
+                    .dispatch(),
+                None => request.dispatch(),
+            }
+        };
+
+        assert_eq!(Status::Ok, mine(Some("Bearer secret")).status());
+        assert_eq!(Status::Unauthorized, mine(Some("Bearer guess")).status());
+        assert_eq!(Status::Unauthorized, mine(None).status());
+
+        let response = client.get("/blocks").dispatch();
+        assert_eq!(Status::Ok, response.status());
+        let blocks: Vec<Block> = response.into_json().expect("blocks");
+        assert_eq!(2, blocks.len());
+    }
+
+    #[test]
+    fn test_get_latest_blocks() {
+        let client = Client::tracked(super::rocket()).expect("valid rocket instance");
+        for value in ["second", "third"] {
+            client
+                .post("/blocks")
+                .header(ContentType::JSON)
+                .body(format!(r#"{{ "value": "{value}" }}"#))
+                .dispatch();
+        }
+
+        let latest = |uri: &str| -> Vec<Block> {
+            client
+                .get(uri.to_string())
+                .dispatch()
+                .into_json()
+                .expect("blocks")
+        };
+        let payloads = |blocks: Vec<Block>| -> Vec<String> {
+            blocks
+                .iter()
+                .map(|block| block.get_payload().clone())
+                .collect()
+        };
+        assert_eq!(vec!["third"], payloads(latest("/blocks/latest?n=1")));
+        assert_eq!(3, latest("/blocks/latest?n=50").len());
+        assert_eq!(3, latest("/blocks/latest").len());
+        assert!(latest("/blocks/latest?n=0").is_empty());
+    }
 }
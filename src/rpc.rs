@@ -0,0 +1,284 @@
+//! JSON-RPC 2.0 interface to the chain, mirroring the REST routes.
+
+use chain_rs_lib::Chain;
+use rocket::http::Status as HttpStatus;
+use rocket::serde::json::serde_json::{self, json};
+use rocket::serde::json::Value;
+use rocket::serde::{Deserialize, Serialize};
+
+use crate::error::ApiError;
+use crate::idempotency::IdempotencyCache;
+use crate::{StaleAfter, Status};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+const UNAUTHORIZED: i64 = -32001;
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    #[serde(default)]
+    id: Value,
+}
+
+#[derive(Serialize)]
+pub struct Response {
+    jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<Error>,
+    id: Value,
+}
+
+#[derive(Serialize)]
+struct Error {
+    code: i64,
+    message: String,
+}
+
+impl Error {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<ApiError> for Error {
+    fn from(error: ApiError) -> Self {
+        let code = if error.0 == HttpStatus::BadRequest {
+            INVALID_PARAMS
+        } else {
+            SERVER_ERROR
+        };
+        Self::new(code, error.1.message())
+    }
+}
+
+/// Answers a raw JSON-RPC request body. The body is parsed here rather than by a `Json` guard so
+/// malformed requests still get a JSON-RPC error object back. Methods writing to the chain are
+/// refused unless the request is `authorized`, `mineBlock` retries carrying the same `key` get
+/// the block mined the first time.
+pub fn handle(
+    body: &str,
+    chain: &mut Chain,
+    stale_after: &StaleAfter,
+    authorized: bool,
+    key: Option<String>,
+    idempotency: &IdempotencyCache,
+) -> Response {
+    let request: Request = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(error) => return respond(Value::Null, Err(Error::new(PARSE_ERROR, error.to_string()))),
+    };
+
+    if request.jsonrpc != "2.0" {
+        return respond(
+            request.id,
+            Err(Error::new(INVALID_REQUEST, "jsonrpc must be \"2.0\"")),
+        );
+    }
+
+    let result = dispatch(
+        chain,
+        stale_after,
+        &request.method,
+        request.params,
+        authorized,
+        key,
+        idempotency,
+    );
+    respond(request.id, result)
+}
+
+fn respond(id: Value, result: Result<Value, Error>) -> Response {
+    let (result, error) = match result {
+        Ok(result) => (Some(result), None),
+        Err(error) => (None, Some(error)),
+    };
+    Response {
+        jsonrpc: "2.0",
+        result,
+        error,
+        id,
+    }
+}
+
+fn dispatch(
+    chain: &mut Chain,
+    stale_after: &StaleAfter,
+    method: &str,
+    params: Value,
+    authorized: bool,
+    key: Option<String>,
+    idempotency: &IdempotencyCache,
+) -> Result<Value, Error> {
+    match method {
+        "getBlocks" => to_value(chain.get_blocks()),
+        "getBlock" => {
+            let height = param(&params, "height", 0)
+                .and_then(Value::as_u64)
+                .ok_or_else(|| Error::new(INVALID_PARAMS, "expected a block height"))?;
+            let block = chain
+                .get_block_by_height(height)
+                .ok_or_else(|| Error::new(SERVER_ERROR, format!("no block at height {height}")))?;
+            to_value(block)
+        }
+        "mineBlock" => {
+            if !authorized {
+                return Err(Error::new(UNAUTHORIZED, "missing or wrong API key"));
+            }
+            let value = param(&params, "value", 0)
+                .and_then(Value::as_str)
+                .ok_or_else(|| Error::new(INVALID_PARAMS, "expected a payload value"))?;
+            let allow_empty = param(&params, "allow_empty", 1)
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            let hash = crate::mine(chain, value, allow_empty, key, idempotency)?;
+            to_value(chain.get_block_by_hash(&hash))
+        }
+        "getStatus" => to_value(Status::of(chain, stale_after)),
+        "validate" => chain
+            .validate()
+            .map(|()| json!(true))
+            .map_err(|error| Error::new(SERVER_ERROR, format!("{error:?}"))),
+        _ => Err(Error::new(
+            METHOD_NOT_FOUND,
+            format!("unknown method {method}"),
+        )),
+    }
+}
+
+/// Looks a parameter up by name, or by position when params are given as an array.
+fn param<'a>(params: &'a Value, name: &str, position: usize) -> Option<&'a Value> {
+    match params {
+        Value::Object(params) => params.get(name),
+        Value::Array(params) => params.get(position),
+        _ => None,
+    }
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, Error> {
+    serde_json::to_value(value).map_err(|error| Error::new(SERVER_ERROR, error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::http::{ContentType, Header, Status};
+    use rocket::local::blocking::Client;
+    use rocket::serde::json::Value;
+
+    #[test]
+    fn test_get_status() {
+        let client = Client::tracked(crate::rocket()).expect("valid rocket instance");
+
+        let response = client
+            .post("/rpc")
+            .header(ContentType::JSON)
+            .body(r#"{ "jsonrpc": "2.0", "method": "getStatus", "id": 7 }"#)
+            .dispatch();
+        assert_eq!(Status::Ok, response.status());
+
+        let body: Value = response.into_json().expect("rpc response");
+        assert_eq!("2.0", body["jsonrpc"]);
+        assert_eq!(7, body["id"]);
+        assert_eq!(0, body["result"]["height"]);
+        assert_eq!(true, body["result"]["valid"]);
+        assert!(body.get("error").is_none());
+    }
+
+    #[test]
+    fn test_unknown_method() {
+        let client = Client::tracked(crate::rocket()).expect("valid rocket instance");
+
+        let response = client
+            .post("/rpc")
+            .header(ContentType::JSON)
+            .body(r#"{ "jsonrpc": "2.0", "method": "mineGold", "id": "abc" }"#)
+            .dispatch();
+
+        let body: Value = response.into_json().expect("rpc response");
+        assert_eq!("abc", body["id"]);
+        assert_eq!(-32601, body["error"]["code"]);
+        assert!(body.get("result").is_none());
+    }
+
+    #[test]
+    fn test_mine_and_get_block() {
+        let client = Client::tracked(crate::rocket()).expect("valid rocket instance");
+
+        client
+            .post("/rpc")
+            .header(ContentType::JSON)
+            .body(r#"{ "jsonrpc": "2.0", "method": "mineBlock", "params": ["Hello world!"], "id": 1 }"#)
+            .dispatch();
+        let response = client
+            .post("/rpc")
+            .header(ContentType::JSON)
+            .body(
+                r#"{ "jsonrpc": "2.0", "method": "getBlock", "params": { "height": 1 }, "id": 2 }"#,
+            )
+            .dispatch();
+
+        let body: Value = response.into_json().expect("rpc response");
+        assert_eq!("Hello world!", body["result"]["payload"]);
+    }
+
+    #[test]
+    fn test_mine_block_follows_rest_rules() {
+        let client = Client::tracked(crate::rocket()).expect("valid rocket instance");
+        let rpc = |params: &str, key: Option<&str>| -> Value {
+            let mut request = client.post("/rpc").header(ContentType::JSON).body(format!(
+                r#"{{ "jsonrpc": "2.0", "method": "mineBlock", "params": {params}, "id": 1 }}"#
+            ));
+            if let Some(key) = key {
+                request = request.header(Header::new("Idempotency-Key", key.to_string()));
+            }
+            request.dispatch().into_json().expect("rpc response")
+        };
+
+        let body = rpc(r#"["  "]"#, None);
+        assert_eq!(-32602, body["error"]["code"]);
+        assert_eq!("payload must not be empty", body["error"]["message"]);
+        let body = rpc(r#"{ "value": "", "allow_empty": true }"#, None);
+        assert_eq!("", body["result"]["payload"]);
+
+        let first = rpc(r#"["Hello world!"]"#, Some("retry-me"));
+        let retry = rpc(r#"["Hello world!"]"#, Some("retry-me"));
+        assert_eq!(first["result"], retry["result"]);
+
+        let blocks: Vec<Value> = client.get("/blocks").dispatch().into_json().unwrap();
+        assert_eq!(3, blocks.len());
+    }
+
+    #[test]
+    fn test_mine_block_needs_api_key() {
+        let figment = rocket::Config::figment().merge(("api_key", "secret"));
+        let client =
+            Client::tracked(crate::build(rocket::custom(figment))).expect("valid rocket instance");
+
+        let response = client
+            .post("/rpc")
+            .header(ContentType::JSON)
+            .body(r#"{ "jsonrpc": "2.0", "method": "mineBlock", "params": ["Hello world!"], "id": 1 }"#)
+            .dispatch();
+        let body: Value = response.into_json().expect("rpc response");
+        assert_eq!(-32001, body["error"]["code"]);
+
+        let response = client
+            .post("/rpc")
+            .header(ContentType::JSON)
+            .body(r#"{ "jsonrpc": "2.0", "method": "getStatus", "id": 2 }"#)
+            .dispatch();
+        let body: Value = response.into_json().expect("rpc response");
+        assert_eq!(0, body["result"]["height"]);
+    }
+}